@@ -0,0 +1,117 @@
+use anyhow::{
+    Context,
+    ensure,
+};
+use cocsim::{
+    GameConfig,
+    Map,
+    ValidatedMap,
+    WithCount,
+    WithMaxHousingSpace,
+    attack_optimizer::{
+        Army,
+        InitialAttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+    },
+    spells::SpellModelEnum,
+    units::UnitModelEnum,
+    war_assignment::{
+        WarMember,
+        assign_war_attacks as assign,
+        war_stars_distributions,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    Value,
+    json,
+};
+
+use crate::consts::{
+    WAR_MAX_ATTACKS_PER_MEMBER,
+    WAR_MAX_BASES,
+    WAR_MAX_MEMBERS,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WarMemberRequest {
+    pub units: WithMaxHousingSpace<MAX_ARMY_HOUSING_SPACE, WithCount<UnitModelEnum>>,
+    pub spells: WithMaxHousingSpace<MAX_SPELLS_HOUSING_SPACE, WithCount<SpellModelEnum>>,
+    /// Plan template member uses on every base.
+    pub plan: InitialAttackPlan,
+}
+
+/// Clan members and enemy bases of war.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignWarAttacksRequest {
+    pub bases: Vec<Map>,
+    pub members: Vec<WarMemberRequest>,
+    #[serde(default = "default_attacks_per_member")]
+    pub attacks_per_member: usize,
+}
+
+fn default_attacks_per_member() -> usize {
+    1
+}
+
+/// Stars distribution of each member's attack on each base (`stars`) and
+/// attacks maximizing expected total stars (`assignment`).
+pub fn assign_war_attacks(request: AssignWarAttacksRequest) -> anyhow::Result<Value> {
+    ensure!(
+        request.bases.len() <= WAR_MAX_BASES,
+        "Too many bases (max = {WAR_MAX_BASES}, got = {})",
+        request.bases.len()
+    );
+    ensure!(
+        request.members.len() <= WAR_MAX_MEMBERS,
+        "Too many members (max = {WAR_MAX_MEMBERS}, got = {})",
+        request.members.len()
+    );
+    ensure!(
+        (1..=WAR_MAX_ATTACKS_PER_MEMBER).contains(&request.attacks_per_member),
+        "Attacks per member should be in range [1; {WAR_MAX_ATTACKS_PER_MEMBER}], got {}",
+        request.attacks_per_member
+    );
+
+    let bases = request
+        .bases
+        .into_iter()
+        .enumerate()
+        .map(|(i, map)| ValidatedMap::try_from(map).with_context(|| format!("Invalid base {i}")))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let members = request
+        .members
+        .into_iter()
+        .map(|member| WarMember {
+            army: Army {
+                units: member.units.to_vec(),
+                spells: member.spells.to_vec(),
+            },
+            plan: member.plan,
+        })
+        .collect::<Vec<_>>();
+
+    let stars = war_stars_distributions(
+        &members,
+        &bases,
+        &GameConfig::default(),
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+    )?;
+    let assignment = assign(&stars, request.attacks_per_member);
+
+    Ok(json!({
+        "stars": stars,
+        "assignment": assignment,
+    }))
+}
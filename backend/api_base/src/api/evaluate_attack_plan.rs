@@ -0,0 +1,78 @@
+use anyhow::{
+    Context,
+    ensure,
+};
+use cocsim::{
+    GameConfig,
+    Map,
+    ValidatedMap,
+    WithCount,
+    WithMaxHousingSpace,
+    attack_optimizer::{
+        Army,
+        AttackObjective,
+        AttackPlanExecutionStats,
+        InitialAttackPlan,
+        execute_attack_plan_batch,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+    },
+    spells::SpellModelEnum,
+    units::UnitModelEnum,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::consts::EVALUATE_ATTACK_PLAN_MAX_MAPS;
+
+/// Plan template evaluated on each of maps, e.g. bases of clan war.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateAttackPlanRequest {
+    pub maps: Vec<Map>,
+    pub units: WithMaxHousingSpace<MAX_ARMY_HOUSING_SPACE, WithCount<UnitModelEnum>>,
+    pub spells: WithMaxHousingSpace<MAX_SPELLS_HOUSING_SPACE, WithCount<SpellModelEnum>>,
+    pub plan: InitialAttackPlan,
+    #[serde(default)]
+    pub objective: AttackObjective,
+}
+
+/// Stats of plan on each map, in request order.
+pub fn evaluate_attack_plan(
+    request: EvaluateAttackPlanRequest,
+) -> anyhow::Result<Vec<AttackPlanExecutionStats>> {
+    ensure!(
+        request.maps.len() <= EVALUATE_ATTACK_PLAN_MAX_MAPS,
+        "Too many maps (max = {EVALUATE_ATTACK_PLAN_MAX_MAPS}, got = {})",
+        request.maps.len()
+    );
+
+    let maps = request
+        .maps
+        .into_iter()
+        .enumerate()
+        .map(|(i, map)| ValidatedMap::try_from(map).with_context(|| format!("Invalid map {i}")))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let army = Army {
+        units: request.units.to_vec(),
+        spells: request.spells.to_vec(),
+    };
+    let config = GameConfig {
+        objective: request.objective,
+        ..Default::default()
+    };
+
+    execute_attack_plan_batch(
+        &maps,
+        &config,
+        |map| request.plan.clamped_executor_actions(&army, map),
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+    )
+}
@@ -1,5 +1,6 @@
 use cocsim::{
     Game,
+    GameConfig,
     spells::{
         HasteSpellModel,
         HealingSpellModel,
@@ -27,7 +28,7 @@ use crate::{
 pub fn get_showcase_attack() -> Value {
     let (map, _) = load_test_map(SHOWCASE_MAP).expect("Map should be loaded successfully");
 
-    let mut game = Game::new(&map, true, None);
+    let mut game = Game::new(&map, &GameConfig::default(), true, None);
 
     for _i in 0..10 {
         game.spawn_attack_unit(
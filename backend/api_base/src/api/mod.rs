@@ -1,11 +1,22 @@
+mod assign_war_attacks;
 mod compose_base_images;
+mod evaluate_attack_plan;
 mod get_game_types;
 mod get_showcase_attack;
 mod get_showcase_attack_base_image;
 mod optimize_attack;
 mod reverse_projection;
 
+pub use assign_war_attacks::{
+    AssignWarAttacksRequest,
+    WarMemberRequest,
+    assign_war_attacks,
+};
 pub use compose_base_images::compose_base_images;
+pub use evaluate_attack_plan::{
+    EvaluateAttackPlanRequest,
+    evaluate_attack_plan,
+};
 pub use get_game_types::get_game_types;
 pub use get_showcase_attack::get_showcase_attack;
 pub use get_showcase_attack_base_image::get_showcase_attack_base_image;
@@ -0,0 +1,23 @@
+use std::time::Instant;
+
+/// Source of time for optimization time budget. [`Instant`] panics on wasm,
+/// so wasm builds provide their own.
+pub trait Clock: Send {
+    /// Seconds since arbitrary moment.
+    fn now(&self) -> f64;
+}
+
+/// [`Clock`] of native builds.
+pub struct InstantClock(Instant);
+
+impl Default for InstantClock {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+
+impl Clock for InstantClock {
+    fn now(&self) -> f64 {
+        self.0.elapsed().as_secs_f64()
+    }
+}
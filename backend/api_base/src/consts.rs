@@ -1,7 +1,19 @@
 pub const FPS: usize = 60;
 pub const SHOWCASE_MAP: &str = "Single Player/No Flight Zone";
 
+pub const OPTIMIZE_ATTACK_RANDOM_STEPS: usize = 10;
+pub const OPTIMIZE_ATTACK_RANDOM_PLANS_PER_STEP: usize = 100;
 pub const OPTIMIZE_ATTACK_STEPS: usize = 20;
+/// Limit of simulated annealing steps in optimization request.
+pub const OPTIMIZE_ATTACK_MAX_STEPS: usize = 1000;
 pub const OPTIMIZE_ATTACK_ITERATIONS: usize = 2000;
 pub const OPTIMIZE_ATTACK_ITERATIONS_PER_STEP: usize =
     OPTIMIZE_ATTACK_ITERATIONS / OPTIMIZE_ATTACK_STEPS;
+
+/// Limit of maps in plan evaluation request.
+pub const EVALUATE_ATTACK_PLAN_MAX_MAPS: usize = 50;
+
+/// Limits of war assignment request.
+pub const WAR_MAX_BASES: usize = 50;
+pub const WAR_MAX_MEMBERS: usize = 50;
+pub const WAR_MAX_ATTACKS_PER_MEMBER: usize = 2;
@@ -1,8 +1,20 @@
 use cocsim::{
     Game,
+    GameConfig,
     Shape,
+    ValidatedMap,
+    attack_optimizer::{
+        AttackPlanExecutor,
+        AttackPlanExecutorAction,
+    },
 };
 use serde::Serialize;
+use serde_json::{
+    Value,
+    to_value,
+};
+
+use crate::consts::FPS;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -74,3 +86,27 @@ impl DtoGameRenderer {
         });
     }
 }
+
+/// Frames of single `actions` execution, same as `result` message of
+/// [`crate::optimize_attack`].
+pub fn render_attack_plan(
+    map: &ValidatedMap,
+    config: &GameConfig,
+    actions: Vec<AttackPlanExecutorAction>,
+) -> Value {
+    let mut game = Game::new(map, config, true, None);
+    let mut plan_executor = AttackPlanExecutor::new(actions, map, config);
+
+    let mut renderer = DtoGameRenderer::new(1);
+
+    plan_executor.tick(&mut game);
+    renderer.draw(&mut game);
+
+    while !game.done() && (!plan_executor.is_empty() || game.is_attacker_team_present()) {
+        plan_executor.tick(&mut game); // no problem calling it twice on first loop iteration
+        game.tick(1.0 / FPS as f32);
+        renderer.draw(&mut game);
+    }
+
+    to_value(renderer.finish(&mut game)).expect("Should not fail")
+}
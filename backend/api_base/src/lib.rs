@@ -1,5 +1,27 @@
 mod api;
+mod clock;
 mod consts;
 mod dto_game_renderer;
+mod optimize_attack_job;
+mod optimize_attack_protocol;
 
 pub use api::*;
+pub use clock::{
+    Clock,
+    InstantClock,
+};
+pub use dto_game_renderer::render_attack_plan;
+pub use optimize_attack_job::{
+    OptimizeAttackJob,
+    OptimizeAttackJobCheckpoint,
+    OptimizeAttackJobRequest,
+    OptimizeAttackStage,
+    ParetoFrontPlan,
+};
+pub use optimize_attack_protocol::{
+    OPTIMIZE_ATTACK_PROTOCOL_VERSION,
+    OptimizeAttackClientMessage,
+    OptimizeAttackProgress,
+    OptimizeAttackServerMessage,
+    OptimizeAttackStart,
+};
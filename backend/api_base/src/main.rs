@@ -0,0 +1,556 @@
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::{
+    Context,
+    ensure,
+};
+use api_base::{
+    InstantClock,
+    OptimizeAttackJob,
+    OptimizeAttackJobRequest,
+    OptimizeAttackStage,
+    render_attack_plan,
+};
+use clap::{
+    Parser,
+    Subcommand,
+    builder::RangedU64ValueParser,
+};
+use cocsim::{
+    Game,
+    GameConfig,
+    Map,
+    ValidatedMap,
+    WithCount,
+    WithMaxHousingSpace,
+    attack_optimizer::{
+        Army,
+        AttackObjective,
+        AttackPlanExecutor,
+        AttackPlanExecutorAction,
+        InitialAttackPlan,
+        execute_attack_plan,
+        execute_attack_plan_batch,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+        RNG_INITIAL_STATE,
+    },
+    defense_optimizer::{
+        DefenseLayoutOptimizer,
+        DefenseLayoutOptimizerParams,
+    },
+    spells::SpellModelEnum,
+    test_maps::load_test_map,
+    units::UnitModelEnum,
+};
+use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+    de::DeserializeOwned,
+};
+use serde_json::{
+    Value,
+    json,
+};
+
+const DEFEND_STEPS: usize = 50;
+
+#[derive(Parser)]
+#[command(about = "Simulates and optimizes Clash of Clans attacks without browser.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Executes plan with different seeds and prints stats and destruction
+    /// events of the first execution as JSON.
+    Simulate {
+        /// Path to map JSON or name of test map, e.g. "Single Player/No Flight
+        /// Zone".
+        #[arg(short, long)]
+        map: String,
+        /// Path to plan JSON, as written by `optimize`.
+        #[arg(short, long)]
+        plan: PathBuf,
+        #[arg(
+            short,
+            long,
+            default_value_t = ATTACK_PLAN_EXECUTIONS_COUNT,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        executions: usize,
+        /// "destructionAndTime" or "destruction".
+        #[arg(long, default_value = "destructionAndTime", value_parser = parse_objective)]
+        objective: AttackObjective,
+    },
+    /// Executes plan on each of maps and prints their stats as JSON, ranked by
+    /// expected stars.
+    Batch {
+        /// Paths to map JSON or names of test maps.
+        #[arg(short, long, required = true)]
+        map: Vec<String>,
+        /// Path to plan JSON, as written by `optimize`.
+        #[arg(short, long)]
+        plan: PathBuf,
+        #[arg(
+            short,
+            long,
+            default_value_t = ATTACK_PLAN_EXECUTIONS_COUNT,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        executions: usize,
+        #[arg(long, default_value = "destructionAndTime", value_parser = parse_objective)]
+        objective: AttackObjective,
+    },
+    /// Optimizes attack of army, prints progress of each step to stderr and
+    /// writes plan JSON of the best attack.
+    Optimize {
+        /// Path to map JSON or name of test map.
+        #[arg(short, long)]
+        map: String,
+        /// Path to army JSON: units and spells, same as in optimization
+        /// request.
+        #[arg(short, long)]
+        army: PathBuf,
+        /// Path to plan JSON optimization starts from. Optional.
+        #[arg(short, long)]
+        initial_plan: Option<PathBuf>,
+        /// Path to JSON of models army can be composed of, same as
+        /// `availableArmy` of optimization request. Needed by
+        /// `v1-composition` stages, written plan has the composition found.
+        #[arg(long)]
+        available_army: Option<PathBuf>,
+        /// Path to JSON list of optimizer stages. Random plans followed by
+        /// simulated annealing if not present.
+        #[arg(short, long)]
+        pipeline: Option<PathBuf>,
+        #[arg(long, default_value = "destructionAndTime", value_parser = parse_objective)]
+        objective: AttackObjective,
+        /// Seconds optimization can take. Optional.
+        #[arg(short, long)]
+        time_budget: Option<f64>,
+        /// Path to plan JSON output. Printed to stdout if not present.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Path to JSON output of Pareto front of the last stage, if its
+        /// optimizer is multi-objective (e.g. `v1-pareto`). Each plan has
+        /// deployed units and spells, so it can be simulated. Optional.
+        #[arg(long)]
+        pareto_front: Option<PathBuf>,
+    },
+    /// Moves buildings of map to minimize expected stars of the best of
+    /// armies, each army attacks with plan optimized against the layout.
+    /// Prints progress of each step to stderr and writes map JSON of the final
+    /// layout.
+    Defend {
+        /// Path to map JSON or name of test map.
+        #[arg(short, long)]
+        map: String,
+        /// Path to JSON list of armies, each with units and spells.
+        #[arg(short, long)]
+        armies: PathBuf,
+        /// Path to JSON of defense optimizer parameters. Optional.
+        #[arg(short, long)]
+        params: Option<PathBuf>,
+        #[arg(short, long, default_value_t = DEFEND_STEPS)]
+        steps: usize,
+        /// Path to map JSON output. Printed to stdout if not present.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Checks that map is valid.
+    Validate {
+        /// Path to map JSON or name of test map.
+        map: String,
+    },
+    /// Writes frames of single plan execution, same as shown by frontend.
+    Render {
+        /// Path to map JSON or name of test map.
+        #[arg(short, long)]
+        map: String,
+        /// Path to plan JSON.
+        #[arg(short, long)]
+        plan: PathBuf,
+        /// Path to frames JSON output. Printed to stdout if not present.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArmyFile {
+    units: WithMaxHousingSpace<MAX_ARMY_HOUSING_SPACE, WithCount<UnitModelEnum>>,
+    spells: WithMaxHousingSpace<MAX_SPELLS_HOUSING_SPACE, WithCount<SpellModelEnum>>,
+}
+
+impl ArmyFile {
+    fn army(&self) -> Army {
+        Army {
+            units: self.units.to_vec(),
+            spells: self.spells.to_vec(),
+        }
+    }
+}
+
+/// Army with placement of its groups. Self-contained, unlike
+/// [`InitialAttackPlan`], which takes group counts from army.
+#[derive(Serialize, Deserialize)]
+struct PlanFile {
+    #[serde(flatten)]
+    army: ArmyFile,
+    plan: InitialAttackPlan,
+}
+
+impl PlanFile {
+    fn executor_actions(
+        &self,
+        map: &ValidatedMap,
+    ) -> anyhow::Result<Vec<AttackPlanExecutorAction>> {
+        self.plan.executor_actions(&self.army.army(), map)
+    }
+
+    fn clamped_executor_actions(
+        &self,
+        map: &ValidatedMap,
+    ) -> anyhow::Result<Vec<AttackPlanExecutorAction>> {
+        self.plan.clamped_executor_actions(&self.army.army(), map)
+    }
+}
+
+/// Destruction change during execution.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Event {
+    time_elapsed: f32,
+    percentage_destroyed: f32,
+    stars: u32,
+}
+
+fn parse_objective(value: &str) -> serde_json::Result<AttackObjective> {
+    serde_json::from_value(Value::String(value.to_owned()))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let json = fs::read_to_string(path).with_context(|| format!("Can't read {path:?}"))?;
+
+    serde_json::from_str(&json).with_context(|| format!("Can't parse {path:?}"))
+}
+
+/// Writes pretty JSON to `output` or stdout if it's not present.
+fn write_json(output: Option<&Path>, value: &impl Serialize) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+
+    match output {
+        Some(path) => fs::write(path, json).with_context(|| format!("Can't write {path:?}")),
+        None => {
+            println!("{json}");
+
+            Ok(())
+        }
+    }
+}
+
+/// Map from JSON file if `map` is an existing path, test map otherwise.
+fn load_map(map: &str) -> anyhow::Result<ValidatedMap> {
+    if Path::new(map).is_file() {
+        let map = read_json::<Map>(Path::new(map))?;
+
+        ValidatedMap::try_from(map)
+    } else {
+        let (map, _) = load_test_map(map).with_context(|| format!("Can't load map {map:?}"))?;
+
+        Ok(map)
+    }
+}
+
+/// Single execution with the same seed as the first execution of
+/// [`execute_attack_plan`].
+fn destruction_events(
+    map: &ValidatedMap,
+    config: &GameConfig,
+    actions: Vec<AttackPlanExecutorAction>,
+) -> Vec<Event> {
+    let (map, _) = map.variants().next().expect("Map has variants");
+    let mut game = Game::new(map, config, false, Some(Pcg64Mcg::new(RNG_INITIAL_STATE)));
+    let mut attack_plan_executor = AttackPlanExecutor::new(actions, map, config);
+    let mut result = Vec::<Event>::new();
+
+    while !game.done() && (!attack_plan_executor.is_empty() || game.is_attacker_team_present()) {
+        attack_plan_executor.tick(&mut game);
+        game.tick(1.0 / ATTACK_PLAN_EXECUTOR_TPS as f32);
+
+        let percentage_destroyed = game.percentage_destroyed();
+        let stars = game.stars();
+
+        if result.last().is_none_or(|event| {
+            event.percentage_destroyed != percentage_destroyed || event.stars != stars
+        }) && percentage_destroyed > 0.0
+        {
+            result.push(Event {
+                time_elapsed: game.time_elapsed(),
+                percentage_destroyed,
+                stars,
+            });
+        }
+    }
+
+    result
+}
+
+fn simulate(
+    map: &str,
+    plan: &Path,
+    executions: usize,
+    objective: AttackObjective,
+) -> anyhow::Result<()> {
+    let map = load_map(map)?;
+    let plan = read_json::<PlanFile>(plan)?;
+    let config = GameConfig {
+        objective,
+        ..Default::default()
+    };
+    let actions = plan.executor_actions(&map)?;
+
+    let stats = execute_attack_plan(
+        &map,
+        &config,
+        &actions,
+        executions,
+        ATTACK_PLAN_EXECUTOR_TPS,
+    );
+    let events = destruction_events(&map, &config, actions);
+
+    write_json(
+        None,
+        &json!({
+            "stats": stats,
+            "events": events,
+        }),
+    )
+}
+
+fn batch(
+    maps: &[String],
+    plan: &Path,
+    executions: usize,
+    objective: AttackObjective,
+) -> anyhow::Result<()> {
+    let validated_maps = maps
+        .iter()
+        .map(|map| load_map(map))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let plan = read_json::<PlanFile>(plan)?;
+    let config = GameConfig {
+        objective,
+        ..Default::default()
+    };
+
+    let stats = execute_attack_plan_batch(
+        &validated_maps,
+        &config,
+        |map| plan.clamped_executor_actions(map),
+        executions,
+        ATTACK_PLAN_EXECUTOR_TPS,
+    )?;
+    let mut result = maps.iter().zip(stats).collect::<Vec<_>>();
+
+    result.sort_by(|(_, a), (_, b)| {
+        b.avg_stars
+            .total_cmp(&a.avg_stars)
+            .then(b.score.total_cmp(&a.score))
+    });
+
+    write_json(
+        None,
+        &result
+            .into_iter()
+            .map(|(map, stats)| json!({ "map": map, "stats": stats }))
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn optimize(
+    map: &str,
+    army: &Path,
+    initial_plan: Option<&Path>,
+    available_army: Option<&Path>,
+    pipeline: Option<&Path>,
+    objective: AttackObjective,
+    time_budget: Option<f64>,
+    output: Option<&Path>,
+    pareto_front: Option<&Path>,
+) -> anyhow::Result<()> {
+    let map = load_map(map)?;
+    let army = read_json::<ArmyFile>(army)?;
+    let initial_plan = initial_plan
+        .map(read_json::<PlanFile>)
+        .transpose()?
+        .map(|plan| plan.plan);
+    let request = OptimizeAttackJobRequest {
+        map: (*map).clone(),
+        units: army.units,
+        spells: army.spells,
+        initial_plan,
+        available_army: available_army.map(read_json).transpose()?,
+        pipeline: match pipeline {
+            Some(pipeline) => read_json(pipeline)?,
+            None => OptimizeAttackStage::default_pipeline(),
+        },
+        objective,
+        time_budget,
+    };
+
+    let mut job = OptimizeAttackJob::from_request(request, Box::new(InstantClock::default()))?;
+
+    while !job.is_done() {
+        let progress = job.step()?;
+
+        eprintln!("{}", serde_json::to_string(&progress)?);
+    }
+
+    if let Some(pareto_front) = pareto_front {
+        write_json(Some(pareto_front), &job.pareto_front())?;
+    }
+
+    let army = job.army();
+
+    write_json(
+        output,
+        &PlanFile {
+            army: ArmyFile {
+                units: WithMaxHousingSpace::new(&army.units)?,
+                spells: WithMaxHousingSpace::new(&army.spells)?,
+            },
+            plan: job.best_plan().expect("Best plan exists here"),
+        },
+    )
+}
+
+fn defend(
+    map: &str,
+    armies: &Path,
+    params: Option<&Path>,
+    steps: usize,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    ensure!(steps > 0, "Steps should be positive");
+
+    let map = load_map(map)?;
+    let armies = read_json::<Vec<ArmyFile>>(armies)?;
+    let params = params
+        .map(read_json::<DefenseLayoutOptimizerParams>)
+        .transpose()?
+        .unwrap_or_default();
+    let mut optimizer = DefenseLayoutOptimizer::new(
+        map,
+        armies.iter().map(ArmyFile::army).collect(),
+        GameConfig::default(),
+        params,
+    )?;
+
+    for step in 0..steps {
+        optimizer.step()?;
+
+        let (_, stats) = optimizer.current().expect("Layout is evaluated here");
+
+        eprintln!(
+            "{}",
+            json!({
+                "step": step,
+                "bestAttack": stats.best_attack,
+                "expectedStars": stats.expected_stars(),
+                "percentageDestroyed": stats.percentage_destroyed(),
+                "timeElapsed": stats.attacks[stats.best_attack].avg_time_elapsed,
+            })
+        );
+    }
+
+    let (layout, _) = optimizer.current().expect("Layout is evaluated here");
+
+    write_json(output, &**layout)
+}
+
+fn validate(map: &str) -> anyhow::Result<()> {
+    let map = load_map(map)?;
+
+    println!(
+        "Map is valid: {} buildings, {} trap zones, {} variants",
+        map.buildings.len(),
+        map.trap_zones.len(),
+        map.variants_count()
+    );
+
+    Ok(())
+}
+
+fn render(map: &str, plan: &Path, output: Option<&Path>) -> anyhow::Result<()> {
+    let map = load_map(map)?;
+    let plan = read_json::<PlanFile>(plan)?;
+    let actions = plan.executor_actions(&map)?;
+
+    write_json(
+        output,
+        &render_attack_plan(&map, &GameConfig::default(), actions),
+    )
+}
+
+fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Simulate {
+            map,
+            plan,
+            executions,
+            objective,
+        } => simulate(&map, &plan, executions, objective),
+        Command::Batch {
+            map,
+            plan,
+            executions,
+            objective,
+        } => batch(&map, &plan, executions, objective),
+        Command::Optimize {
+            map,
+            army,
+            initial_plan,
+            available_army,
+            pipeline,
+            objective,
+            time_budget,
+            output,
+            pareto_front,
+        } => optimize(
+            &map,
+            &army,
+            initial_plan.as_deref(),
+            available_army.as_deref(),
+            pipeline.as_deref(),
+            objective,
+            time_budget,
+            output.as_deref(),
+            pareto_front.as_deref(),
+        ),
+        Command::Defend {
+            map,
+            armies,
+            params,
+            steps,
+            output,
+        } => defend(&map, &armies, params.as_deref(), steps, output.as_deref()),
+        Command::Validate { map } => validate(&map),
+        Command::Render { map, plan, output } => render(&map, &plan, output.as_deref()),
+    }
+}
@@ -0,0 +1,516 @@
+use anyhow::{
+    Context,
+    ensure,
+};
+use cocsim::{
+    GameConfig,
+    Map,
+    ValidatedMap,
+    WithCount,
+    WithMaxHousingSpace,
+    attack_optimizer::{
+        ATTACK_OPTIMIZER_NAMES,
+        Army,
+        AttackObjective,
+        AttackOptimizer,
+        AttackOptimizerParams,
+        AttackOptimizerState,
+        AttackPlanExecutionStats,
+        AvailableArmy,
+        InitialAttackPlan,
+        executions_count,
+        new_attack_optimizer,
+        v1::ParetoObjectives,
+    },
+    consts::{
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+    },
+    spells::SpellModelEnum,
+    units::UnitModelEnum,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use crate::{
+    clock::Clock,
+    consts::{
+        OPTIMIZE_ATTACK_ITERATIONS_PER_STEP,
+        OPTIMIZE_ATTACK_MAX_STEPS,
+        OPTIMIZE_ATTACK_RANDOM_PLANS_PER_STEP,
+        OPTIMIZE_ATTACK_RANDOM_STEPS,
+        OPTIMIZE_ATTACK_STEPS,
+    },
+    dto_game_renderer::render_attack_plan,
+    optimize_attack_protocol::OptimizeAttackProgress,
+};
+
+/// Optimizer run for a number of steps, starting from the best plan of the
+/// previous stage.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeAttackStage {
+    /// One of [`ATTACK_OPTIMIZER_NAMES`].
+    pub optimizer: String,
+    #[serde(default)]
+    pub params: AttackOptimizerParams,
+    pub steps: usize,
+}
+
+impl OptimizeAttackStage {
+    /// Random plans, then simulated annealing of the best one.
+    pub fn default_pipeline() -> Vec<Self> {
+        vec![
+            OptimizeAttackStage {
+                optimizer: "v1-random".to_owned(),
+                params: AttackOptimizerParams {
+                    plans_per_step: Some(OPTIMIZE_ATTACK_RANDOM_PLANS_PER_STEP),
+                    ..Default::default()
+                },
+                steps: OPTIMIZE_ATTACK_RANDOM_STEPS,
+            },
+            OptimizeAttackStage {
+                optimizer: "v1-simulated-annealing".to_owned(),
+                params: AttackOptimizerParams {
+                    iterations_per_step: Some(OPTIMIZE_ATTACK_ITERATIONS_PER_STEP),
+                    ..Default::default()
+                },
+                steps: OPTIMIZE_ATTACK_STEPS,
+            },
+        ]
+    }
+}
+
+/// Everything needed to continue [`OptimizeAttackJob`]. Optimizer of the
+/// current stage continues from its saved state if it supports it (see
+/// [`AttackOptimizer::state`]), otherwise it's restarted from the best plan
+/// found so far with the rest of its steps.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeAttackJobCheckpoint {
+    pub map: Map,
+    pub army: Army,
+    pub objective: AttackObjective,
+    pub pipeline: Vec<OptimizeAttackStage>,
+    pub stage: usize,
+    pub stage_step: usize,
+    pub best_plan: Option<InitialAttackPlan>,
+    #[serde(default)]
+    pub optimizer_state: Option<AttackOptimizerState>,
+    #[serde(default)]
+    pub available_army: Option<AvailableArmy>,
+    #[serde(default)]
+    pub time_budget: Option<f64>,
+    /// Seconds spent on steps.
+    #[serde(default)]
+    pub elapsed: f64,
+}
+
+/// Plan of Pareto front found by multi-objective stage (e.g. `v1-pareto`).
+/// Self-contained like plans of `cocsim` CLI: `units` and `spells` are the
+/// deployed part of army.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParetoFrontPlan {
+    pub units: Vec<WithCount<UnitModelEnum>>,
+    pub spells: Vec<WithCount<SpellModelEnum>>,
+    pub plan: InitialAttackPlan,
+    pub objectives: ParetoObjectives,
+    pub score: f32,
+}
+
+/// Parameters of [`OptimizeAttackJob`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeAttackJobRequest {
+    pub map: Map,
+    pub units: WithMaxHousingSpace<MAX_ARMY_HOUSING_SPACE, WithCount<UnitModelEnum>>,
+    pub spells: WithMaxHousingSpace<MAX_SPELLS_HOUSING_SPACE, WithCount<SpellModelEnum>>,
+    /// Optimization starts from random plans if not present.
+    #[serde(default)]
+    pub initial_plan: Option<InitialAttackPlan>,
+    /// Models `v1-composition` stages compose army of. Army of the next
+    /// stages is composition of the best plan. Required by these stages.
+    #[serde(default)]
+    pub available_army: Option<AvailableArmy>,
+    #[serde(default = "OptimizeAttackStage::default_pipeline")]
+    pub pipeline: Vec<OptimizeAttackStage>,
+    #[serde(default)]
+    pub objective: AttackObjective,
+    /// Seconds optimization can take, it's stopped after the first step
+    /// exceeding it even if pipeline isn't done.
+    #[serde(default)]
+    pub time_budget: Option<f64>,
+}
+
+/// Attack optimization run by [`crate::optimize_attack`]: stages of
+/// pipeline, each one starting from the best plan of the previous one. Runs
+/// step by step, so it can be saved to [`OptimizeAttackJobCheckpoint`]
+/// between steps and extended with more steps after it's done.
+pub struct OptimizeAttackJob {
+    map: ValidatedMap,
+    /// Army of the current stage, replaced by composition of the best plan
+    /// when stage searching it is done.
+    army: Army,
+    available_army: Option<AvailableArmy>,
+    config: GameConfig,
+    pipeline: Vec<OptimizeAttackStage>,
+    /// Index of the current stage in [`Self::pipeline`].
+    stage: usize,
+    /// Steps done in the current stage.
+    stage_step: usize,
+    optimizer: Box<dyn AttackOptimizer>,
+    /// Plan the current stage started from.
+    seed: Option<InitialAttackPlan>,
+    time_budget: Option<f64>,
+    /// Seconds spent on steps, including ones done before restoring from
+    /// checkpoint.
+    elapsed: f64,
+    clock: Box<dyn Clock>,
+}
+
+impl OptimizeAttackJob {
+    pub fn from_request(
+        request: OptimizeAttackJobRequest,
+        clock: Box<dyn Clock>,
+    ) -> anyhow::Result<Self> {
+        // checkpoints of extended jobs can have more steps
+        ensure!(
+            request
+                .pipeline
+                .iter()
+                .map(|stage| stage.steps)
+                .sum::<usize>()
+                <= OPTIMIZE_ATTACK_MAX_STEPS,
+            "Pipeline should have at most {OPTIMIZE_ATTACK_MAX_STEPS} steps"
+        );
+
+        let map = ValidatedMap::try_from(request.map)?;
+        let army = Army {
+            units: request.units.to_vec(),
+            spells: request.spells.to_vec(),
+        };
+
+        Self::with_stage(
+            map,
+            army,
+            request.objective,
+            request.pipeline,
+            0,
+            0,
+            request.initial_plan,
+            None,
+            request.available_army,
+            request.time_budget,
+            0.0,
+            clock,
+        )
+    }
+
+    pub fn from_checkpoint(
+        checkpoint: OptimizeAttackJobCheckpoint,
+        clock: Box<dyn Clock>,
+    ) -> anyhow::Result<Self> {
+        let map = ValidatedMap::try_from(checkpoint.map)?;
+        let army = Army {
+            units: WithMaxHousingSpace::<MAX_ARMY_HOUSING_SPACE, _>::new(&checkpoint.army.units)?
+                .to_vec(),
+            spells: WithMaxHousingSpace::<MAX_SPELLS_HOUSING_SPACE, _>::new(
+                &checkpoint.army.spells,
+            )?
+            .to_vec(),
+        };
+
+        Self::with_stage(
+            map,
+            army,
+            checkpoint.objective,
+            checkpoint.pipeline,
+            checkpoint.stage,
+            checkpoint.stage_step,
+            checkpoint.best_plan,
+            checkpoint.optimizer_state,
+            checkpoint.available_army,
+            checkpoint.time_budget,
+            checkpoint.elapsed,
+            clock,
+        )
+    }
+
+    /// Job at `stage_step` of `stage`, which starts from `seed`. Its
+    /// optimizer continues from `optimizer_state` if it's present.
+    #[allow(clippy::too_many_arguments)]
+    fn with_stage(
+        map: ValidatedMap,
+        army: Army,
+        objective: AttackObjective,
+        pipeline: Vec<OptimizeAttackStage>,
+        stage: usize,
+        stage_step: usize,
+        seed: Option<InitialAttackPlan>,
+        optimizer_state: Option<AttackOptimizerState>,
+        available_army: Option<AvailableArmy>,
+        time_budget: Option<f64>,
+        elapsed: f64,
+        clock: Box<dyn Clock>,
+    ) -> anyhow::Result<Self> {
+        ensure!(!pipeline.is_empty(), "Pipeline should have stages");
+        ensure!(
+            time_budget.is_none_or(|time_budget| time_budget.is_finite() && time_budget > 0.0),
+            "Time budget should be positive"
+        );
+        ensure!(
+            stage < pipeline.len() && stage_step <= pipeline[stage].steps,
+            "Pipeline stage is out of range"
+        );
+
+        for stage in &pipeline {
+            ensure!(
+                ATTACK_OPTIMIZER_NAMES.contains(&stage.optimizer.as_str()),
+                "Unknown attack optimizer: {}",
+                stage.optimizer
+            );
+            ensure!(stage.steps > 0, "Pipeline stage should have steps");
+            ensure!(
+                stage.optimizer != "v1-composition" || available_army.is_some(),
+                "Attack optimizer {} needs available army",
+                stage.optimizer
+            );
+
+            stage.params.validate()?;
+        }
+
+        let config = GameConfig {
+            objective,
+            ..Default::default()
+        };
+        let mut optimizer = new_stage_optimizer(
+            &pipeline[stage],
+            pipeline[stage].steps - stage_step,
+            &map,
+            &army,
+            available_army.as_ref(),
+            &config,
+            seed.as_ref(),
+        )?;
+
+        if let Some(optimizer_state) = optimizer_state {
+            optimizer.restore(optimizer_state)?;
+        }
+
+        Ok(Self {
+            map,
+            army,
+            available_army,
+            config,
+            pipeline,
+            stage,
+            stage_step,
+            optimizer,
+            seed,
+            time_budget,
+            elapsed,
+            clock,
+        })
+    }
+
+    pub fn checkpoint(&self) -> OptimizeAttackJobCheckpoint {
+        OptimizeAttackJobCheckpoint {
+            map: (*self.map).clone(),
+            army: self.army(),
+            objective: self.config.objective,
+            pipeline: self.pipeline.clone(),
+            stage: self.stage,
+            stage_step: self.stage_step,
+            best_plan: self.best_plan(),
+            optimizer_state: self.optimizer.state(),
+            available_army: self.available_army.clone(),
+            time_budget: self.time_budget,
+            elapsed: self.elapsed,
+        }
+    }
+
+    /// Pipeline is done or time budget is exceeded.
+    pub fn is_done(&self) -> bool {
+        self.stage + 1 == self.pipeline.len() && self.stage_done()
+            || self
+                .time_budget
+                .is_some_and(|time_budget| self.elapsed >= time_budget)
+    }
+
+    fn stage_done(&self) -> bool {
+        self.stage_step == self.pipeline[self.stage].steps
+    }
+
+    /// Steps of all pipeline stages.
+    pub fn steps(&self) -> usize {
+        self.pipeline.iter().map(|stage| stage.steps).sum()
+    }
+
+    /// Steps of job with `steps` after it's extended by `extra_steps`, error
+    /// if job can't be extended by them.
+    pub fn extended_steps(steps: usize, extra_steps: usize) -> anyhow::Result<usize> {
+        ensure!(
+            extra_steps > 0,
+            "Job should be extended by at least one step"
+        );
+        ensure!(
+            extra_steps <= OPTIMIZE_ATTACK_MAX_STEPS.saturating_sub(steps),
+            "Job should have at most {OPTIMIZE_ATTACK_MAX_STEPS} steps"
+        );
+
+        Ok(steps + extra_steps)
+    }
+
+    /// Adds stage repeating the last one for `steps` more steps, works for
+    /// done jobs too. Time budget is removed, so added steps are run. Steps
+    /// should be checked by [`Self::extended_steps`].
+    pub fn extend(&mut self, steps: usize) {
+        self.time_budget = None;
+
+        let last_stage = self.pipeline.last().expect("Pipeline has stages");
+
+        self.pipeline.push(OptimizeAttackStage {
+            steps,
+            ..last_stage.clone()
+        });
+    }
+
+    /// Army of [`Self::best_plan`].
+    pub fn army(&self) -> Army {
+        self.optimizer
+            .best_army()
+            .unwrap_or_else(|| self.army.clone())
+    }
+
+    /// Best plan found so far or plan the current stage started from.
+    pub fn best_plan(&self) -> Option<InitialAttackPlan> {
+        match self.optimizer.best() {
+            Some((actions, _)) => Some(InitialAttackPlan::from_executor_actions(&actions)),
+            None => self.seed.clone(),
+        }
+    }
+
+    /// Stats of the best plan found so far, [`None`] before the first step.
+    pub fn best_plan_stats(&self) -> Option<AttackPlanExecutionStats> {
+        self.optimizer.best().map(|(_, stats)| stats)
+    }
+
+    /// Pareto front of the current stage, empty if its optimizer isn't
+    /// multi-objective.
+    pub fn pareto_front(&self) -> Vec<ParetoFrontPlan> {
+        self.optimizer
+            .pareto_front()
+            .into_iter()
+            .map(|pareto_plan| ParetoFrontPlan {
+                units: pareto_plan.army.units,
+                spells: pareto_plan.army.spells,
+                plan: InitialAttackPlan::from_executor_actions(&pareto_plan.actions),
+                objectives: pareto_plan.objectives,
+                score: pareto_plan.stats.score,
+            })
+            .collect()
+    }
+
+    /// Runs one step and returns stats of the best plan. Starts the next
+    /// stage if the current one is done.
+    pub fn step(&mut self) -> anyhow::Result<OptimizeAttackProgress> {
+        ensure!(!self.is_done(), "Job is done");
+
+        if self.stage_done() {
+            self.seed = Some(
+                self.best_plan()
+                    .context("Pipeline stage is done without plan")?,
+            );
+            self.army = self.army();
+            self.stage += 1;
+            self.stage_step = 0;
+            self.optimizer = new_stage_optimizer(
+                &self.pipeline[self.stage],
+                self.pipeline[self.stage].steps,
+                &self.map,
+                &self.army,
+                self.available_army.as_ref(),
+                &self.config,
+                self.seed.as_ref(),
+            )?;
+        }
+
+        let generation = self.pipeline[..self.stage]
+            .iter()
+            .map(|stage| stage.steps)
+            .sum::<usize>()
+            + self.stage_step;
+
+        let start = self.clock.now();
+        let executions_count_before = executions_count();
+
+        self.optimizer.step();
+        self.stage_step += 1;
+
+        let step_time = self.clock.now() - start;
+
+        self.elapsed += step_time;
+
+        let best_plan_stats = self
+            .best_plan_stats()
+            .context("Attack optimizer has no plan after step")?;
+
+        Ok(OptimizeAttackProgress {
+            generation,
+            best_score: best_plan_stats.score,
+            best_score_standard_error: best_plan_stats.score_standard_error,
+            min_time_elapsed: best_plan_stats.min_time_elapsed,
+            avg_time_elapsed: best_plan_stats.avg_time_elapsed,
+            max_time_elapsed: best_plan_stats.max_time_elapsed,
+            avg_percentage_destroyed: best_plan_stats.avg_percentage_destroyed,
+            samples_count: best_plan_stats.samples_count,
+            elapsed: self.elapsed,
+            simulations_per_second: (executions_count() - executions_count_before) as f64
+                / step_time.max(f64::EPSILON),
+        })
+    }
+
+    /// Frames of the best plan execution. Plan the current stage started from
+    /// is rendered if its optimizer hasn't found any plan yet.
+    pub fn render_best_plan(&self) -> anyhow::Result<Value> {
+        let best_plan_actions = match self.optimizer.best() {
+            Some((actions, _)) => actions,
+            None => self
+                .seed
+                .as_ref()
+                .context("Job has no plan to render")?
+                .executor_actions(&self.army, &self.map)?,
+        };
+
+        Ok(render_attack_plan(
+            &self.map,
+            &self.config,
+            best_plan_actions,
+        ))
+    }
+}
+
+fn new_stage_optimizer(
+    stage: &OptimizeAttackStage,
+    steps: usize,
+    map: &ValidatedMap,
+    army: &Army,
+    available_army: Option<&AvailableArmy>,
+    config: &GameConfig,
+    seed: Option<&InitialAttackPlan>,
+) -> anyhow::Result<Box<dyn AttackOptimizer>> {
+    new_attack_optimizer(
+        &stage.optimizer,
+        &stage.params,
+        steps,
+        map.clone(),
+        army.clone(),
+        available_army,
+        config.clone(),
+        seed,
+    )
+}
@@ -0,0 +1,89 @@
+use anyhow::ensure;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use crate::{
+    Clock,
+    OptimizeAttackJob,
+    OptimizeAttackJobRequest,
+};
+
+/// Incremented on incompatible changes of [`OptimizeAttackClientMessage`] or
+/// [`OptimizeAttackServerMessage`].
+pub const OPTIMIZE_ATTACK_PROTOCOL_VERSION: u32 = 1;
+
+/// Messages of [`crate::optimize_attack`] client.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OptimizeAttackClientMessage {
+    Start(OptimizeAttackStart),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeAttackStart {
+    /// [`OPTIMIZE_ATTACK_PROTOCOL_VERSION`] client was built with.
+    pub version: u32,
+    #[serde(flatten)]
+    pub request: OptimizeAttackJobRequest,
+}
+
+impl OptimizeAttackStart {
+    pub fn into_job(self, clock: Box<dyn Clock>) -> anyhow::Result<OptimizeAttackJob> {
+        ensure!(
+            self.version == OPTIMIZE_ATTACK_PROTOCOL_VERSION,
+            "Unsupported protocol version {}, expected {OPTIMIZE_ATTACK_PROTOCOL_VERSION}",
+            self.version
+        );
+
+        OptimizeAttackJob::from_request(self.request, clock)
+    }
+}
+
+/// Messages of [`crate::optimize_attack`] server.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OptimizeAttackServerMessage {
+    /// Sent first by webserver, job can be followed by id after reconnect.
+    Job {
+        id: String,
+    },
+    Progress(OptimizeAttackProgress),
+    /// Frames of the best plan, the last message.
+    Result {
+        result: Value,
+    },
+    /// Invalid request or failed optimization, the last message.
+    Error {
+        message: String,
+    },
+}
+
+impl OptimizeAttackServerMessage {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Should not fail")
+    }
+}
+
+/// Stats of the best plan after optimization step.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeAttackProgress {
+    /// Step of the whole optimization, random plan steps included.
+    pub generation: usize,
+    pub best_score: f32,
+    pub best_score_standard_error: f32,
+    pub min_time_elapsed: f32,
+    pub avg_time_elapsed: f32,
+    pub max_time_elapsed: f32,
+    pub avg_percentage_destroyed: f32,
+    /// Number of executions stats are estimated from.
+    pub samples_count: usize,
+    /// Seconds since optimization start.
+    pub elapsed: f64,
+    /// Attack plan executions per second during this step.
+    pub simulations_per_second: f64,
+}
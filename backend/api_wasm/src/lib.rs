@@ -1,6 +1,9 @@
 use std::cell::RefCell;
 
-use api_base::SendRecvError;
+use api_base::{
+    Clock,
+    SendRecvError,
+};
 use bytes::Bytes;
 use log::{
     Level,
@@ -15,6 +18,7 @@ use web_sys::{
     BlobPropertyBag,
     js_sys::{
         Array,
+        Date,
         Function,
         JSON,
         JsString,
@@ -82,6 +86,15 @@ pub async fn get_showcase_attack() -> JsValue {
     JSON::parse(&serde_json::to_string(&api_base::get_showcase_attack()).unwrap()).unwrap()
 }
 
+/// [`Clock`] based on `Date.now()`, [`std::time::Instant`] panics on wasm.
+struct DateClock;
+
+impl Clock for DateClock {
+    fn now(&self) -> f64 {
+        Date::now() / 1000.0
+    }
+}
+
 #[wasm_bindgen]
 pub struct OptimizeAttackApiStream(RefCell<OptimizeAttackApiStreamState>);
 
@@ -162,7 +175,7 @@ impl OptimizeAttackApiStream {
             None => Err(SendRecvError::Cancel),
         };
 
-        rayon::spawn(move || api_base::optimize_attack(send, recv).unwrap());
+        rayon::spawn(move || api_base::optimize_attack(send, recv, DateClock).unwrap());
 
         loop {
             select! {
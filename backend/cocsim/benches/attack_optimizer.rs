@@ -1,12 +1,11 @@
 use cocsim::{
+    GameConfig,
     ValidatedMap,
     WithCount,
     attack_optimizer::{
         Army,
-        v1::{
-            AttackOptimizer,
-            GeneticAttackOptimizer,
-        },
+        AttackOptimizer,
+        v1::GeneticAttackOptimizer,
     },
     spells::LightningSpellModel,
     test_maps::load_test_map,
@@ -21,7 +20,14 @@ use criterion::{
 };
 
 fn optimize_attack(map: &ValidatedMap, army: &Army) {
-    let mut optimizer = GeneticAttackOptimizer::new(map.clone(), army.clone(), 0.02, 0.05);
+    let mut optimizer = GeneticAttackOptimizer::new(
+        map.clone(),
+        army.clone(),
+        GameConfig::default(),
+        None,
+        0.02,
+        0.05,
+    );
 
     optimizer.step();
 }
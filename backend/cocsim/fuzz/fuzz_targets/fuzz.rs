@@ -3,6 +3,7 @@
 use arbitrary::Arbitrary;
 use cocsim::{
     Game,
+    GameConfig,
     Map,
     ValidatedMap,
     attack_optimizer::{
@@ -33,8 +34,10 @@ fuzz_target!(|inputs: FuzzInputs| {
         // uncomment next line when debugging crash
         //dbg!(inputs);
 
-        let mut game = Game::new(&map, false, None);
-        let mut plan_executor = AttackPlanExecutor::new(inputs.plan.executor_actions(&map));
+        let config = GameConfig::default();
+        let mut game = Game::new(&map, &config, false, None);
+        let mut plan_executor =
+            AttackPlanExecutor::new(inputs.plan.executor_actions(&map), &map, &config);
 
         while !game.done() && (game.is_attacker_team_present() || !plan_executor.is_empty()) {
             plan_executor.tick(&mut game);
@@ -1,4 +1,8 @@
 use arbitrary::Arbitrary;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
     spells::SpellModelEnum,
@@ -6,7 +10,7 @@ use crate::{
     with_housing_space::WithCount,
 };
 
-#[derive(Debug, Clone, Arbitrary)]
+#[derive(Serialize, Deserialize, Debug, Clone, Arbitrary)]
 pub struct Army {
     pub units: Vec<WithCount<UnitModelEnum>>,
     pub spells: Vec<WithCount<SpellModelEnum>>,
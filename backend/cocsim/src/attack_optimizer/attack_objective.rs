@@ -0,0 +1,15 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// What attack optimizers maximize, see [`super::AttackPlanExecution::score`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AttackObjective {
+    /// Destruction percentage, then remaining attack time.
+    #[default]
+    DestructionAndTime,
+    /// Destruction percentage only, slow attacks are as good as fast ones.
+    Destruction,
+}
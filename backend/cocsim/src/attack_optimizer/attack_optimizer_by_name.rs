@@ -0,0 +1,250 @@
+use anyhow::{
+    bail,
+    ensure,
+};
+use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        AvailableArmy,
+        InitialAttackPlan,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan,
+        v1,
+        v2,
+        v3,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        RNG_INITIAL_STATE,
+    },
+};
+
+const PLANS_PER_STEP: usize = 100;
+const ITERATIONS_PER_STEP: usize = 100;
+const V1_MUTATION_TEMPERATURE_DECAY: f32 = 0.02;
+const V1_MERGE_PROBABILITY_DECAY: f64 = 0.05;
+const V1_PARETO_POPULATION_SIZE: usize = 40;
+const V1_COMPOSITION_POPULATION_SIZE: usize = 40;
+const V3_LIPO_ITERATIONS_PER_STEP: usize = 10;
+const V3_BAYESIAN_EVALUATIONS_PER_STEP: usize = 10;
+
+/// Names accepted by [`new_attack_optimizer`].
+pub const ATTACK_OPTIMIZER_NAMES: &[&str] = &[
+    "v1-random",
+    "v1-genetic",
+    "v1-simulated-annealing",
+    "v1-pareto",
+    "v1-composition",
+    "v2-random",
+    "v2-simulated-annealing",
+    "v3-lipo",
+    "v3-cma-es",
+    "v3-bayesian",
+];
+
+/// Parameters of optimizers created by [`new_attack_optimizer`]. Missing
+/// parameters have default values, ones not used by optimizer are ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AttackOptimizerParams {
+    /// Plans evaluated per step by random optimizers.
+    pub plans_per_step: Option<usize>,
+    /// Iterations per step of simulated annealing and LIPO optimizers.
+    pub iterations_per_step: Option<usize>,
+    /// Length of v1 simulated annealing cooling schedule. Fitted to steps
+    /// budget by default.
+    pub iterations: Option<usize>,
+    pub mutation_temperature_decay: Option<f32>,
+    pub merge_probability_decay: Option<f64>,
+    pub population_size: Option<usize>,
+    /// Plans evaluated per step by bayesian optimizer.
+    pub evaluations_per_step: Option<usize>,
+}
+
+impl AttackOptimizerParams {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in [
+            ("plansPerStep", self.plans_per_step),
+            ("iterationsPerStep", self.iterations_per_step),
+            ("iterations", self.iterations),
+            ("populationSize", self.population_size),
+            ("evaluationsPerStep", self.evaluations_per_step),
+        ] {
+            ensure!(
+                value != Some(0),
+                "Optimizer parameter {name} should be positive"
+            );
+        }
+
+        ensure!(
+            self.mutation_temperature_decay
+                .is_none_or(|decay| decay.is_finite() && decay >= 0.0),
+            "Optimizer parameter mutationTemperatureDecay should be non-negative"
+        );
+        ensure!(
+            self.merge_probability_decay
+                .is_none_or(|decay| decay.is_finite() && decay >= 0.0),
+            "Optimizer parameter mergeProbabilityDecay should be non-negative"
+        );
+
+        Ok(())
+    }
+}
+
+/// Creates optimizer by one of [`ATTACK_OPTIMIZER_NAMES`]. `steps` is the
+/// number of [`AttackOptimizer::step`] calls optimizer will get, cooling
+/// schedules are fitted to it. If `initial_plan` is provided, it's converted
+/// to optimizer plan encoding and used as starting point. `v1-composition`
+/// searches composition of `available_army` instead of using `army` and
+/// ignores `initial_plan`.
+#[allow(clippy::too_many_arguments)]
+pub fn new_attack_optimizer(
+    name: &str,
+    params: &AttackOptimizerParams,
+    steps: usize,
+    map: ValidatedMap,
+    army: Army,
+    available_army: Option<&AvailableArmy>,
+    config: GameConfig,
+    initial_plan: Option<&InitialAttackPlan>,
+) -> anyhow::Result<Box<dyn AttackOptimizer>> {
+    ensure!(
+        !map.drop_zone_free_tiles().is_empty(),
+        "Map has no drop zone"
+    );
+
+    let mut rng = Pcg64Mcg::new(RNG_INITIAL_STATE);
+    let evaluate = |actions: &[AttackPlanExecutorAction]| -> AttackPlanExecutionStats {
+        execute_attack_plan(
+            &map,
+            &config,
+            actions,
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        )
+    };
+    let v1_initial_plan = match initial_plan {
+        Some(initial_plan) if name.starts_with("v1-") => {
+            let plan =
+                v1::AttackPlan::from_initial_plan(initial_plan, &army, &map, &config, &mut rng)?;
+            let stats = evaluate(&plan.executor_actions(&map));
+
+            Some((plan, stats))
+        }
+        _ => None,
+    };
+    let v2_initial_plan = match initial_plan {
+        Some(initial_plan) if name.starts_with("v2-") => {
+            let plan =
+                v2::AttackPlan::from_initial_plan(initial_plan, &army, &map, &config, &mut rng)?;
+            let stats = evaluate(&plan.executor_actions(&army, &map));
+
+            Some((plan, stats))
+        }
+        _ => None,
+    };
+    let v3_plan = v3::AttackPlan {
+        map: map.clone(),
+        army: army.clone(),
+        config: config.clone(),
+    };
+    let v3_initial_x = match initial_plan {
+        Some(initial_plan) if name.starts_with("v3-") => Some(v3_plan.initial_x(initial_plan)?),
+        _ => None,
+    };
+
+    let plans_per_step = params.plans_per_step.unwrap_or(PLANS_PER_STEP);
+    let iterations_per_step = params.iterations_per_step.unwrap_or(ITERATIONS_PER_STEP);
+
+    Ok(match name {
+        "v1-random" => Box::new(v1::RandomAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v1_initial_plan,
+            plans_per_step,
+        )),
+        "v1-genetic" => Box::new(v1::GeneticAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v1_initial_plan,
+            params
+                .mutation_temperature_decay
+                .unwrap_or(V1_MUTATION_TEMPERATURE_DECAY),
+            params
+                .merge_probability_decay
+                .unwrap_or(V1_MERGE_PROBABILITY_DECAY),
+        )),
+        "v1-simulated-annealing" => Box::new(v1::SimulatedAnnealingAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v1_initial_plan,
+            params.iterations.unwrap_or(steps * iterations_per_step),
+            iterations_per_step,
+        )),
+        "v1-pareto" => Box::new(v1::ParetoAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v1_initial_plan,
+            params.population_size.unwrap_or(V1_PARETO_POPULATION_SIZE),
+        )),
+        "v1-composition" => {
+            let Some(available_army) = available_army else {
+                bail!("Attack optimizer {name} needs available army");
+            };
+
+            Box::new(v1::CompositionAttackOptimizer::new(
+                map,
+                available_army.clone(),
+                config,
+                params
+                    .population_size
+                    .unwrap_or(V1_COMPOSITION_POPULATION_SIZE),
+            )?)
+        }
+        "v2-random" => Box::new(v2::RandomAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v2_initial_plan,
+            plans_per_step,
+        )),
+        "v2-simulated-annealing" => Box::new(v2::SimulatedAnnealingAttackOptimizer::new(
+            map,
+            army,
+            config,
+            v2_initial_plan,
+            iterations_per_step,
+        )),
+        "v3-lipo" => Box::new(v3::LipoAttackOptimizer::new(
+            v3_plan,
+            v3_initial_x,
+            params
+                .iterations_per_step
+                .unwrap_or(V3_LIPO_ITERATIONS_PER_STEP),
+        )),
+        "v3-cma-es" => Box::new(v3::CmaEsAttackOptimizer::new(v3_plan, v3_initial_x)),
+        "v3-bayesian" => Box::new(v3::BayesianAttackOptimizer::new(
+            v3_plan,
+            v3_initial_x,
+            params
+                .evaluations_per_step
+                .unwrap_or(V3_BAYESIAN_EVALUATIONS_PER_STEP),
+        )),
+        _ => bail!("Unknown attack optimizer: {name}"),
+    })
+}
@@ -1,83 +1,197 @@
 use core::f32;
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
 
 use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
     Game,
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
+        AttackObjective,
         AttackPlanExecutor,
         attack_plan_executor::AttackPlanExecutorAction,
     },
-    consts::{
-        MAX_ATTACK_DURATION,
-        RNG_INITIAL_STATE,
-    },
+    consts::RNG_INITIAL_STATE,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct AttackPlanExecution {
     pub time_elapsed: f32,
     /// In range [0.0; 100.0]
     pub percentage_destroyed: f32,
+    pub stars: u32,
+    /// Execution was stopped because it couldn't reach cutoff score. Its
+    /// score is below cutoff, but is not final.
+    pub terminated_early: bool,
+    /// Index of seed, plan is executed with each seed on every map variant.
+    pub seed: usize,
+    /// Weight of map variant (see [`ValidatedMap::from_variants`]) this
+    /// execution was run on.
+    pub weight: f32,
 }
 
-#[derive(Clone, Debug)]
+impl AttackPlanExecution {
+    /// Score of single execution. [`AttackPlanExecutionStats::score`] is
+    /// average of these.
+    pub fn score(&self, config: &GameConfig) -> f32 {
+        score(self.percentage_destroyed, self.time_elapsed, config)
+    }
+
+    /// Best score execution can reach if `time_elapsed` seconds have passed.
+    pub fn max_score(time_elapsed: f32, config: &GameConfig) -> f32 {
+        score(100.0, time_elapsed, config)
+    }
+}
+
+fn score(percentage_destroyed: f32, time_elapsed: f32, config: &GameConfig) -> f32 {
+    match config.objective {
+        AttackObjective::DestructionAndTime => {
+            percentage_destroyed * 4.0 + (config.max_attack_duration - time_elapsed)
+        }
+        AttackObjective::Destruction => percentage_destroyed * 4.0,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct AttackPlanExecutionStats {
     pub executions: Vec<AttackPlanExecution>,
     pub min_time_elapsed: f32,
     pub max_time_elapsed: f32,
     pub avg_time_elapsed: f32,
     pub avg_percentage_destroyed: f32,
+    /// Expected number of stars.
+    pub avg_stars: f32,
     /// More is better.
     pub score: f32,
+    /// Standard error of [`Self::score`] estimate. Zero if there is only one
+    /// execution.
+    pub score_standard_error: f32,
+    /// Number of seeds [`Self::score`] is estimated from.
+    pub samples_count: usize,
 }
 
 impl AttackPlanExecutionStats {
-    pub fn new(executions: Vec<AttackPlanExecution>) -> Self {
+    /// Executions with the same seed should be consecutive, one for each map
+    /// variant.
+    pub fn new(executions: Vec<AttackPlanExecution>, config: &GameConfig) -> Self {
         let mut min_time_elapsed = f32::INFINITY;
         let mut max_time_elapsed = f32::NEG_INFINITY;
         let mut avg_time_elapsed = 0.0;
 
         let mut avg_percentage_destroyed = 0.0;
+        let mut avg_stars = 0.0;
+
+        let mut weights_sum = 0.0;
 
         for execution in &executions {
             min_time_elapsed = min_time_elapsed.min(execution.time_elapsed);
             max_time_elapsed = max_time_elapsed.max(execution.time_elapsed);
-            avg_time_elapsed += execution.time_elapsed;
+            avg_time_elapsed += execution.time_elapsed * execution.weight;
 
-            avg_percentage_destroyed += execution.percentage_destroyed;
+            avg_percentage_destroyed += execution.percentage_destroyed * execution.weight;
+            avg_stars += execution.stars as f32 * execution.weight;
+
+            weights_sum += execution.weight;
         }
 
-        avg_time_elapsed /= executions.len() as f32;
-        avg_percentage_destroyed /= executions.len() as f32;
+        avg_time_elapsed /= weights_sum;
+        avg_percentage_destroyed /= weights_sum;
+        avg_stars /= weights_sum;
+
+        let score = score(avg_percentage_destroyed, avg_time_elapsed, config);
+        let samples_count = executions.chunk_by(|a, b| a.seed == b.seed).count();
+        let score_standard_error = if samples_count > 1 {
+            // map variants aren't random, only seeds are, so sample is
+            // weighted score of seed executions
+            let variance = executions
+                .chunk_by(|a, b| a.seed == b.seed)
+                .map(|seed_executions| {
+                    let seed_weights_sum = seed_executions
+                        .iter()
+                        .map(|execution| execution.weight)
+                        .sum::<f32>();
+                    let seed_score = seed_executions
+                        .iter()
+                        .map(|execution| execution.weight * execution.score(config))
+                        .sum::<f32>()
+                        / seed_weights_sum;
+
+                    (seed_score - score).powi(2)
+                })
+                .sum::<f32>()
+                / (samples_count - 1) as f32;
+
+            (variance / samples_count as f32).sqrt()
+        } else {
+            0.0
+        };
 
         Self {
             executions,
-            score: avg_percentage_destroyed * 4.0 + (MAX_ATTACK_DURATION - avg_time_elapsed),
+            score,
+            score_standard_error,
+            samples_count,
             min_time_elapsed,
             avg_time_elapsed,
             max_time_elapsed,
             avg_percentage_destroyed,
+            avg_stars,
         }
     }
+
+    /// Lower bound of [`Self::score`] confidence interval.
+    pub fn score_lower_bound(&self, confidence: f32) -> f32 {
+        self.score - confidence * self.score_standard_error
+    }
+
+    /// Upper bound of [`Self::score`] confidence interval.
+    pub fn score_upper_bound(&self, confidence: f32) -> f32 {
+        self.score + confidence * self.score_standard_error
+    }
 }
 
+/// Executions started by [`execute_attack_plan_single`] in this process.
+static EXECUTIONS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of attack plan executions run by this process so far, used to
+/// measure throughput. Executions of all optimizers running at the same time
+/// are counted.
+pub fn executions_count() -> usize {
+    EXECUTIONS_COUNT.load(Ordering::Relaxed)
+}
+
+/// Executes plan on map variant with `weight`.
 pub fn execute_attack_plan_single(
     map: &ValidatedMap,
+    weight: f32,
+    config: &GameConfig,
     actions: &[AttackPlanExecutorAction],
-    i: usize,
+    seed: usize,
     delta_time: f32,
+    cutoff: Option<f32>,
 ) -> AttackPlanExecution {
-    // maybe set enable_collision_grid to true in future (when ground units will be
-    // added)
+    EXECUTIONS_COUNT.fetch_add(1, Ordering::Relaxed);
+
+    // collision grid is created lazily by pathfinding if ground units are present
     let mut game = Game::new(
         map,
+        config,
         false,
-        Some(Pcg64Mcg::new(RNG_INITIAL_STATE + i as u128)),
+        Some(Pcg64Mcg::new(RNG_INITIAL_STATE + seed as u128)),
     );
-    let mut attack_plan_executor = AttackPlanExecutor::new(actions.to_owned());
+    let mut attack_plan_executor = AttackPlanExecutor::new(actions.to_owned(), map, config);
     let mut early_loose = false;
+    let mut terminated_early = false;
 
     while !game.done() {
         if !game.is_attacker_team_present() && attack_plan_executor.is_empty() {
@@ -86,12 +200,20 @@ pub fn execute_attack_plan_single(
             break;
         }
 
+        if cutoff.is_some_and(|cutoff| {
+            AttackPlanExecution::max_score(game.time_elapsed(), config) < cutoff
+        }) {
+            terminated_early = true;
+
+            break;
+        }
+
         attack_plan_executor.tick(&mut game);
         game.tick(delta_time);
     }
 
     let time_elapsed = if early_loose {
-        MAX_ATTACK_DURATION
+        config.max_attack_duration
     } else {
         game.time_elapsed()
     };
@@ -99,5 +221,9 @@ pub fn execute_attack_plan_single(
     AttackPlanExecution {
         time_elapsed,
         percentage_destroyed: game.percentage_destroyed(),
+        stars: game.stars(),
+        terminated_early,
+        seed,
+        weight,
     }
 }
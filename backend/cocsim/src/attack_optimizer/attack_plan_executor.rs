@@ -1,14 +1,13 @@
-use nalgebra::Vector2;
+use nalgebra::{
+    Rotation2,
+    Vector2,
+};
 
 use crate::{
     Game,
+    GameConfig,
+    ValidatedMap,
     WithCount,
-    consts::{
-        SPELL_DROP_COOLDOWN,
-        SPELL_GROUP_DROP_COOLDOWN,
-        UNIT_DROP_COOLDOWN,
-        UNIT_GROUP_DROP_COOLDOWN,
-    },
     spells::{
         SpellModel,
         SpellModelEnum,
@@ -31,10 +30,67 @@ struct AttackPlanExecutorActionInternal {
 
 #[derive(Clone)]
 pub enum Spawnable {
+    /// All units are dropped at action position.
     UnitGroup(WithCount<UnitModelEnum>),
+    /// Units are spread evenly along segment from action position to `end`.
+    UnitLine {
+        units: WithCount<UnitModelEnum>,
+        end: Vector2<f32>,
+    },
+    /// Units are spread evenly along arc starting at action position and
+    /// rotating around `center` by `angle` radians.
+    UnitArc {
+        units: WithCount<UnitModelEnum>,
+        center: Vector2<f32>,
+        angle: f32,
+    },
+    /// Units are dropped in turn at action position and each of `points`.
+    UnitSplit {
+        units: WithCount<UnitModelEnum>,
+        points: Vec<Vector2<f32>>,
+    },
     SpellGroup(WithCount<SpellModelEnum>),
 }
 
+impl Spawnable {
+    /// Unit positions for unit spawnables (not clamped to drop zone),
+    /// [`None`] for spells.
+    fn unit_positions(&self, position: Vector2<f32>) -> Option<Vec<Vector2<f32>>> {
+        // fraction of the way to place i-th of `count` units
+        let fraction = |i: usize, count: usize| (i as f32 + 0.5) / count as f32;
+
+        match self {
+            Self::UnitGroup(units) => Some(vec![position; units.count]),
+            Self::UnitLine { units, end } => Some(
+                (0..units.count)
+                    .map(|i| position + (end - position) * fraction(i, units.count))
+                    .collect(),
+            ),
+            Self::UnitArc {
+                units,
+                center,
+                angle,
+            } => Some(
+                (0..units.count)
+                    .map(|i| {
+                        center
+                            + Rotation2::new(angle * fraction(i, units.count)) * (position - center)
+                    })
+                    .collect(),
+            ),
+            Self::UnitSplit { units, points } => Some(
+                (0..units.count)
+                    .map(|i| match i % (points.len() + 1) {
+                        0 => position,
+                        point_index => points[point_index - 1],
+                    })
+                    .collect(),
+            ),
+            Self::SpellGroup(_) => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AttackPlanExecutorAction {
     pub spawnable: Spawnable,
@@ -47,7 +103,13 @@ pub struct AttackPlanExecutor {
 }
 
 impl AttackPlanExecutor {
-    pub fn new(mut actions: Vec<AttackPlanExecutorAction>) -> Self {
+    /// Unit positions of line, arc and split spawnables are clamped to drop
+    /// zone of `map`.
+    pub fn new(
+        mut actions: Vec<AttackPlanExecutorAction>,
+        map: &ValidatedMap,
+        config: &GameConfig,
+    ) -> Self {
         // sort reversed by drop_time key
         actions.sort_unstable_by(|a, b| b.drop_time.total_cmp(&a.drop_time));
 
@@ -55,21 +117,44 @@ impl AttackPlanExecutor {
         let mut next_drop_time = 0.0f32;
 
         for action in actions {
+            let unit_positions = action.spawnable.unit_positions(action.position);
+            let clamp_to_drop_zone = !matches!(action.spawnable, Spawnable::UnitGroup(_));
+
             match action.spawnable {
-                Spawnable::UnitGroup(unit_group) => {
+                Spawnable::UnitGroup(unit_group)
+                | Spawnable::UnitLine {
+                    units: unit_group, ..
+                }
+                | Spawnable::UnitArc {
+                    units: unit_group, ..
+                }
+                | Spawnable::UnitSplit {
+                    units: unit_group, ..
+                } => {
                     next_drop_time = next_drop_time.max(action.drop_time);
 
-                    for _ in 0..unit_group.count {
+                    for position in unit_positions.unwrap() {
+                        let position = if clamp_to_drop_zone {
+                            // unit can't be dropped anywhere
+                            let Some(position) = map.nearest_drop_zone_point(position) else {
+                                continue;
+                            };
+
+                            position
+                        } else {
+                            position
+                        };
+
                         result.push(AttackPlanExecutorActionInternal {
                             spawnable: SingleSpawnable::Unit(unit_group.value.clone()),
-                            position: action.position,
+                            position,
                             drop_time: next_drop_time,
                         });
 
-                        next_drop_time += UNIT_DROP_COOLDOWN;
+                        next_drop_time += config.unit_drop_cooldown;
                     }
 
-                    next_drop_time += UNIT_GROUP_DROP_COOLDOWN;
+                    next_drop_time += config.unit_group_drop_cooldown;
                 }
                 Spawnable::SpellGroup(spell_group) => {
                     next_drop_time = next_drop_time.max(action.drop_time);
@@ -81,10 +166,10 @@ impl AttackPlanExecutor {
                             drop_time: next_drop_time,
                         });
 
-                        next_drop_time += SPELL_DROP_COOLDOWN;
+                        next_drop_time += config.spell_drop_cooldown;
                     }
 
-                    next_drop_time += SPELL_GROUP_DROP_COOLDOWN;
+                    next_drop_time += config.spell_group_drop_cooldown;
                 }
             }
         }
@@ -0,0 +1,82 @@
+use anyhow::ensure;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    WithCount,
+    WithHousingSpace,
+    attack_optimizer::Army,
+    consts::{
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+    },
+    spells::SpellModelEnum,
+    units::UnitModelEnum,
+};
+
+/// Unit and spell models army can be composed of, used when army
+/// composition is optimized together with the plan.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableArmy {
+    pub units: Vec<UnitModelEnum>,
+    pub spells: Vec<SpellModelEnum>,
+    pub units_housing_space: usize,
+    pub spells_housing_space: usize,
+}
+
+impl AvailableArmy {
+    /// Checks that housing spaces don't exceed [`MAX_ARMY_HOUSING_SPACE`] and
+    /// [`MAX_SPELLS_HOUSING_SPACE`] and that every model fits them.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.units_housing_space <= MAX_ARMY_HOUSING_SPACE,
+            "Units housing space is larger than {MAX_ARMY_HOUSING_SPACE}"
+        );
+        ensure!(
+            self.spells_housing_space <= MAX_SPELLS_HOUSING_SPACE,
+            "Spells housing space is larger than {MAX_SPELLS_HOUSING_SPACE}"
+        );
+
+        for unit in &self.units {
+            ensure!(
+                (1..=self.units_housing_space).contains(&unit.housing_space()),
+                "Unit {unit:?} doesn't fit units housing space"
+            );
+        }
+
+        for spell in &self.spells {
+            ensure!(
+                (1..=self.spells_housing_space).contains(&spell.housing_space()),
+                "Spell {spell:?} doesn't fit spells housing space"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Army with every model taking the whole housing space. Any composition
+    /// is this army with reduced counts.
+    pub fn max_army(&self) -> Army {
+        Army {
+            units: self
+                .units
+                .iter()
+                .map(|unit| WithCount {
+                    value: unit.clone(),
+                    count: self.units_housing_space / unit.housing_space(),
+                })
+                .collect(),
+            spells: self
+                .spells
+                .iter()
+                .map(|spell| WithCount {
+                    value: spell.clone(),
+                    count: self.spells_housing_space / spell.housing_space(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,24 +1,82 @@
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         AttackPlanExecutionStats,
-        attack_plan_execution_stats::execute_attack_plan_single,
+        attack_plan_execution_stats::{
+            AttackPlanExecution,
+            execute_attack_plan_single,
+        },
         attack_plan_executor::AttackPlanExecutorAction,
     },
 };
 
+/// Executes plan with `executions_count` different seeds, each seed on every
+/// map variant (see [`ValidatedMap::from_variants`]).
 pub fn execute_attack_plan(
     map: &ValidatedMap,
+    config: &GameConfig,
     actions: &[AttackPlanExecutorAction],
     executions_count: usize,
     tps: usize,
 ) -> AttackPlanExecutionStats {
+    let seeds = (0..executions_count)
+        .map(|i| (actions, i))
+        .collect::<Vec<_>>();
+
+    AttackPlanExecutionStats::new(
+        execute_attack_plan_seeds(map, config, &seeds, tps, None).concat(),
+        config,
+    )
+}
+
+/// Executes plan made by `plan` for each of `maps` `executions_count` times.
+/// Stats are in `maps` order. `plan` can place plan template on map or find
+/// plan with optimizer.
+pub fn execute_attack_plan_batch(
+    maps: &[ValidatedMap],
+    config: &GameConfig,
+    plan: impl Fn(&ValidatedMap) -> anyhow::Result<Vec<AttackPlanExecutorAction>> + Sync,
+    executions_count: usize,
+    tps: usize,
+) -> anyhow::Result<Vec<AttackPlanExecutionStats>> {
+    maps.iter()
+        .map(|map| {
+            Ok(execute_attack_plan(
+                map,
+                config,
+                &plan(map)?,
+                executions_count,
+                tps,
+            ))
+        })
+        .collect()
+}
+
+/// Executes each plan with given seed index on every map variant. Returns
+/// executions of each seed in variants order. Executions which can't reach
+/// `cutoff` score are terminated early.
+pub fn execute_attack_plan_seeds(
+    map: &ValidatedMap,
+    config: &GameConfig,
+    seeds: &[(&[AttackPlanExecutorAction], usize)],
+    tps: usize,
+    cutoff: Option<f32>,
+) -> Vec<Vec<AttackPlanExecution>> {
     let delta_time = 1.0 / tps as f32;
-    let mut executions = Vec::with_capacity(executions_count);
+    let mut executions = Vec::with_capacity(seeds.len());
 
-    for i in 0..executions_count {
-        executions.push(execute_attack_plan_single(map, &actions, i, delta_time));
+    for (actions, seed) in seeds {
+        executions.push(
+            map.variants()
+                .map(|(variant, weight)| {
+                    execute_attack_plan_single(
+                        variant, weight, config, actions, *seed, delta_time, cutoff,
+                    )
+                })
+                .collect(),
+        );
     }
 
-    AttackPlanExecutionStats::new(executions)
+    executions
 }
@@ -0,0 +1,268 @@
+use anyhow::bail;
+use nalgebra::{
+    Rotation2,
+    Vector2,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        AttackPlanExecutorAction,
+        Spawnable,
+        UnitGroupFormation,
+    },
+    spells::SpellModelEnum,
+    units::UnitModelEnum,
+};
+
+/// Group of hand-crafted plan. Counts are taken from [`Army`]. Positions are
+/// in tiles.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum InitialAttackPlanAction {
+    UnitGroup {
+        unit: UnitModelEnum,
+        x: f32,
+        y: f32,
+        drop_time: f32,
+        #[serde(default)]
+        formation: UnitGroupFormation,
+        /// Formation length in tiles.
+        #[serde(default)]
+        spread: f32,
+    },
+    SpellGroup {
+        spell: SpellModelEnum,
+        x: f32,
+        y: f32,
+        drop_time: f32,
+    },
+}
+
+/// Hand-crafted plan optimization starts from. Unlike
+/// [`crate::attack_optimizer::AttackPlanExecutorAction`] list, it doesn't
+/// depend on plan encoding and can be converted to any of them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct InitialAttackPlan(pub Vec<InitialAttackPlanAction>);
+
+/// Placement of single army group taken from [`InitialAttackPlan`].
+#[derive(Debug, Clone)]
+pub struct InitialGroupPlacement {
+    pub position: Vector2<f32>,
+    pub drop_time: f32,
+    /// [`UnitGroupFormation::Point`] for spells.
+    pub formation: UnitGroupFormation,
+    pub spread: f32,
+}
+
+impl InitialAttackPlan {
+    /// Plan deploying the same groups as `actions` made by
+    /// [`UnitGroupFormation::executor_action`], so plan found by optimizer
+    /// of one encoding can be continued by optimizer of another one.
+    pub fn from_executor_actions(actions: &[AttackPlanExecutorAction]) -> Self {
+        Self(
+            actions
+                .iter()
+                .map(|action| {
+                    let start = action.position;
+                    let (units, formation, middle, spread) = match &action.spawnable {
+                        Spawnable::SpellGroup(spells) => {
+                            return InitialAttackPlanAction::SpellGroup {
+                                spell: spells.value.clone(),
+                                x: start.x,
+                                y: start.y,
+                                drop_time: action.drop_time,
+                            };
+                        }
+                        Spawnable::UnitGroup(units) => {
+                            (units, UnitGroupFormation::Point, start, 0.0)
+                        }
+                        Spawnable::UnitLine { units, end } => (
+                            units,
+                            UnitGroupFormation::Line,
+                            (start + end) / 2.0,
+                            (end - start).norm(),
+                        ),
+                        Spawnable::UnitArc {
+                            units,
+                            center,
+                            angle,
+                        } => {
+                            let radius = start - center;
+
+                            (
+                                units,
+                                UnitGroupFormation::Arc,
+                                center + Rotation2::new(angle / 2.0) * radius,
+                                angle * radius.norm(),
+                            )
+                        }
+                        Spawnable::UnitSplit { units, points } if points.len() == 1 => (
+                            units,
+                            UnitGroupFormation::Split,
+                            (start + points[0]) / 2.0,
+                            (points[0] - start).norm(),
+                        ),
+                        // not made by formations, only the first point is kept
+                        Spawnable::UnitSplit { units, .. } => {
+                            (units, UnitGroupFormation::Point, start, 0.0)
+                        }
+                    };
+
+                    InitialAttackPlanAction::UnitGroup {
+                        unit: units.value.clone(),
+                        x: middle.x,
+                        y: middle.y,
+                        drop_time: action.drop_time,
+                        formation,
+                        spread,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Placements of `army` unit groups followed by spell groups, in army
+    /// order. Groups are matched by model, groups missing in plan are
+    /// [`None`]. Fails if plan has group which is not in `army`.
+    pub fn placements(&self, army: &Army) -> anyhow::Result<Vec<Option<InitialGroupPlacement>>> {
+        let models = army
+            .units
+            .iter()
+            .map(|unit| serde_json::to_value(&unit.value))
+            .chain(
+                army.spells
+                    .iter()
+                    .map(|spell| serde_json::to_value(&spell.value)),
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut result = vec![None; models.len()];
+
+        for action in &self.0 {
+            let (model, groups, placement) = match action {
+                InitialAttackPlanAction::UnitGroup {
+                    unit,
+                    x,
+                    y,
+                    drop_time,
+                    formation,
+                    spread,
+                } => (
+                    serde_json::to_value(unit)?,
+                    0..army.units.len(),
+                    InitialGroupPlacement {
+                        position: Vector2::new(*x, *y),
+                        drop_time: *drop_time,
+                        formation: *formation,
+                        spread: *spread,
+                    },
+                ),
+                InitialAttackPlanAction::SpellGroup {
+                    spell,
+                    x,
+                    y,
+                    drop_time,
+                } => (
+                    serde_json::to_value(spell)?,
+                    army.units.len()..models.len(),
+                    InitialGroupPlacement {
+                        position: Vector2::new(*x, *y),
+                        drop_time: *drop_time,
+                        formation: UnitGroupFormation::Point,
+                        spread: 0.0,
+                    },
+                ),
+            };
+
+            let Some(i) = groups
+                .into_iter()
+                .find(|i| result[*i].is_none() && models[*i] == model)
+            else {
+                bail!("Initial plan group {model} is not in army");
+            };
+
+            result[i] = Some(placement);
+        }
+
+        Ok(result)
+    }
+
+    /// Actions deploying `army` groups exactly as placed in plan, without
+    /// clamping to drop zone or drop time limits. Groups missing in plan are
+    /// not deployed.
+    pub fn executor_actions(
+        &self,
+        army: &Army,
+        map: &ValidatedMap,
+    ) -> anyhow::Result<Vec<AttackPlanExecutorAction>> {
+        let placements = self.placements(army)?;
+        let (unit_placements, spell_placements) = placements.split_at(army.units.len());
+        let center = Vector2::from_element(map.size().total_size() as f32 / 2.0);
+        let mut result = Vec::new();
+
+        for (units, placement) in army.units.iter().zip(unit_placements) {
+            let Some(placement) = placement else {
+                continue;
+            };
+
+            result.push(placement.formation.executor_action(
+                units.clone(),
+                placement.position,
+                center,
+                placement.spread,
+                placement.drop_time,
+            ));
+        }
+
+        for (spells, placement) in army.spells.iter().zip(spell_placements) {
+            let Some(placement) = placement else {
+                continue;
+            };
+
+            result.push(AttackPlanExecutorAction {
+                spawnable: Spawnable::SpellGroup(spells.clone()),
+                position: placement.position,
+                drop_time: placement.drop_time,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Actions of plan applied to another layout. Unit groups dropped at
+    /// single point are moved to the nearest drop zone point of `map` (or
+    /// aren't dropped if drop zone is empty), formations are clamped to it by
+    /// executor.
+    pub fn clamped_executor_actions(
+        &self,
+        army: &Army,
+        map: &ValidatedMap,
+    ) -> anyhow::Result<Vec<AttackPlanExecutorAction>> {
+        let mut actions = self.executor_actions(army, map)?;
+
+        actions.retain_mut(|action| {
+            let Spawnable::UnitGroup(_) = action.spawnable else {
+                return true;
+            };
+            let Some(position) = map.nearest_drop_zone_point(action.position) else {
+                return false;
+            };
+
+            action.position = position;
+
+            true
+        });
+
+        Ok(actions)
+    }
+}
@@ -1,18 +1,106 @@
+use anyhow::bail;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
 mod army;
+mod attack_objective;
+mod attack_optimizer_by_name;
 mod attack_plan_execution_stats;
 mod attack_plan_executor;
+mod available_army;
 #[cfg(not(feature = "rayon"))]
 mod execute_attack_plan_no_rayon;
 #[cfg(feature = "rayon")]
 mod execute_attack_plan_rayon;
+mod initial_attack_plan;
+mod race_attack_plans;
+mod unit_group_formation;
 pub mod v1;
 pub mod v2;
 pub mod v3;
 
 pub use army::Army;
-pub use attack_plan_execution_stats::AttackPlanExecutionStats;
-pub use attack_plan_executor::AttackPlanExecutor;
+pub use attack_objective::AttackObjective;
+pub use attack_optimizer_by_name::{
+    ATTACK_OPTIMIZER_NAMES,
+    AttackOptimizerParams,
+    new_attack_optimizer,
+};
+pub use attack_plan_execution_stats::{
+    AttackPlanExecution,
+    AttackPlanExecutionStats,
+    executions_count,
+};
+pub use attack_plan_executor::{
+    AttackPlanExecutor,
+    AttackPlanExecutorAction,
+    Spawnable,
+};
+pub use available_army::AvailableArmy;
 #[cfg(not(feature = "rayon"))]
-pub use execute_attack_plan_no_rayon::execute_attack_plan;
+pub use execute_attack_plan_no_rayon::{
+    execute_attack_plan,
+    execute_attack_plan_batch,
+    execute_attack_plan_seeds,
+};
 #[cfg(feature = "rayon")]
-pub use execute_attack_plan_rayon::execute_attack_plan;
+pub use execute_attack_plan_rayon::{
+    execute_attack_plan,
+    execute_attack_plan_batch,
+    execute_attack_plan_seeds,
+};
+pub use initial_attack_plan::{
+    InitialAttackPlan,
+    InitialAttackPlanAction,
+    InitialGroupPlacement,
+};
+pub use race_attack_plans::race_attack_plans;
+pub use unit_group_formation::UnitGroupFormation;
+
+/// State saved by [`AttackOptimizer::state`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum AttackOptimizerState {
+    V1Random(v1::RandomAttackOptimizerCheckpoint),
+    V1SimulatedAnnealing(v1::SimulatedAnnealingAttackOptimizerCheckpoint),
+}
+
+/// Optimizer of any generation. Results don't depend on plan encoding, so
+/// optimizers can be chosen at runtime.
+pub trait AttackOptimizer: Send {
+    /// Runs one portion of optimization (generation, batch of iterations,
+    /// etc.).
+    fn step(&mut self);
+
+    /// Best plan found so far or [`None`] if [`Self::step`] wasn't called yet.
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)>;
+
+    /// Plans trading objectives off against each other, found by
+    /// multi-objective optimizers (e.g. [`v1::ParetoAttackOptimizer`]). Empty
+    /// for the others.
+    fn pareto_front(&self) -> Vec<v1::ParetoPlan> {
+        Vec::new()
+    }
+
+    /// Composition of the best plan, found by optimizers searching army
+    /// composition (e.g. [`v1::CompositionAttackOptimizer`]). [`None`] for the
+    /// others, their plans use the whole army.
+    fn best_army(&self) -> Option<Army> {
+        None
+    }
+
+    /// Serializable state (RNG, plans, iteration counters) to continue
+    /// optimization with [`Self::restore`]. [`None`] if optimizer doesn't
+    /// support it.
+    fn state(&self) -> Option<AttackOptimizerState> {
+        None
+    }
+
+    /// Continues optimization from [`Self::state`] of optimizer created with
+    /// the same map, army and config.
+    fn restore(&mut self, _state: AttackOptimizerState) -> anyhow::Result<()> {
+        bail!("Attack optimizer state can't be restored")
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        AttackPlanExecutionStats,
+        attack_plan_execution_stats::AttackPlanExecution,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan_seeds,
+    },
+    consts::{
+        ATTACK_PLAN_RACING_CONFIDENCE,
+        ATTACK_PLAN_RACING_INITIAL_EXECUTIONS_COUNT,
+    },
+};
+
+/// Evaluates `plans` spending executions only on contenders. Every plan is
+/// executed with a few seeds (each on every map variant), then plans whose
+/// score confidence interval is entirely below `incumbent` score or below
+/// another plan's interval are dropped, and the number of seeds of the rest is
+/// doubled until it reaches `max_executions_count`. Executions which can't
+/// reach `incumbent` score are terminated early, which is used only to drop
+/// plans: plans which weren't dropped run such seeds again till the end in
+/// the last round.
+///
+/// Returns stats of every plan in the same order. Stats of plans which weren't
+/// dropped are unbiased. Dropped plans have less than `max_executions_count`
+/// samples, which is reported in [`AttackPlanExecutionStats::samples_count`],
+/// and their score is underestimated if their executions were terminated
+/// early.
+pub fn race_attack_plans(
+    map: &ValidatedMap,
+    config: &GameConfig,
+    plans: &[Vec<AttackPlanExecutorAction>],
+    incumbent: Option<&AttackPlanExecutionStats>,
+    max_executions_count: usize,
+    tps: usize,
+) -> Vec<AttackPlanExecutionStats> {
+    // executions of each plan on every map variant by seed
+    let mut executions = vec![Vec::<Vec<AttackPlanExecution>>::new(); plans.len()];
+    let mut contenders = (0..plans.len()).collect::<Vec<_>>();
+    let mut executions_count =
+        ATTACK_PLAN_RACING_INITIAL_EXECUTIONS_COUNT.min(max_executions_count);
+
+    loop {
+        let last_round = executions_count >= max_executions_count;
+        let seeds = contenders
+            .iter()
+            .flat_map(|&i| {
+                let executions = &executions[i];
+
+                (0..executions_count)
+                    .filter(move |&seed| {
+                        executions.get(seed).is_none_or(|seed_executions| {
+                            last_round
+                                && seed_executions
+                                    .iter()
+                                    .any(|execution| execution.terminated_early)
+                        })
+                    })
+                    .map(move |seed| (i, seed))
+            })
+            .collect::<Vec<_>>();
+        let new_executions = execute_attack_plan_seeds(
+            map,
+            config,
+            &seeds
+                .iter()
+                .map(|&(i, seed)| (plans[i].as_slice(), seed))
+                .collect::<Vec<_>>(),
+            tps,
+            incumbent
+                .filter(|_| !last_round)
+                .map(|incumbent| incumbent.score),
+        );
+
+        for ((i, seed), seed_executions) in seeds.into_iter().zip(new_executions) {
+            if seed < executions[i].len() {
+                executions[i][seed] = seed_executions;
+            } else {
+                executions[i].push(seed_executions);
+            }
+        }
+
+        if last_round {
+            break;
+        }
+
+        let stats = contenders
+            .iter()
+            .map(|&i| AttackPlanExecutionStats::new(executions[i].concat(), config))
+            .collect::<Vec<_>>();
+        let threshold = stats
+            .iter()
+            .map(|stats| stats.score_lower_bound(ATTACK_PLAN_RACING_CONFIDENCE))
+            .chain(incumbent.map(|incumbent| incumbent.score))
+            .fold(f32::NEG_INFINITY, f32::max);
+        let mut stats = stats.into_iter();
+
+        contenders.retain(|_| {
+            stats
+                .next()
+                .unwrap()
+                .score_upper_bound(ATTACK_PLAN_RACING_CONFIDENCE)
+                >= threshold
+        });
+
+        if contenders.is_empty() {
+            break;
+        }
+
+        executions_count = (executions_count * 2).min(max_executions_count);
+    }
+
+    executions
+        .into_iter()
+        .map(|executions| AttackPlanExecutionStats::new(executions.concat(), config))
+        .collect()
+}
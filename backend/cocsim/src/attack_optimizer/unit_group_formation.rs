@@ -0,0 +1,102 @@
+use arbitrary::Arbitrary;
+use nalgebra::{
+    Rotation2,
+    Vector2,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    WithCount,
+    attack_optimizer::attack_plan_executor::{
+        AttackPlanExecutorAction,
+        Spawnable,
+    },
+    units::UnitModelEnum,
+};
+
+/// Shape in which attack plan unit group is deployed. Lets optimizers
+/// discover funnels.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Arbitrary)]
+#[serde(rename_all = "camelCase")]
+pub enum UnitGroupFormation {
+    #[default]
+    Point,
+    Line,
+    Arc,
+    /// Half of units at each end of the line.
+    Split,
+}
+
+impl UnitGroupFormation {
+    pub const ALL: [Self; 4] = [Self::Point, Self::Line, Self::Arc, Self::Split];
+
+    /// Used by optimizers working with real numbers. `x` is expected to be
+    /// from 0 to [`Self::ALL`] length.
+    pub fn from_number(x: f32) -> Self {
+        Self::ALL[(x.max(0.0) as usize).min(Self::ALL.len() - 1)]
+    }
+
+    /// Inverse of [`Self::from_number`], returns middle of the formation
+    /// range.
+    pub fn to_number(self) -> f32 {
+        Self::ALL.iter().position(|x| *x == self).unwrap() as f32 + 0.5
+    }
+
+    /// Action deploying `units` around `position`. Formation is `spread` tiles
+    /// long and goes along circle around `center`, so it follows base edge
+    /// when `center` is map center.
+    pub fn executor_action(
+        self,
+        units: WithCount<UnitModelEnum>,
+        position: Vector2<f32>,
+        center: Vector2<f32>,
+        spread: f32,
+        drop_time: f32,
+    ) -> AttackPlanExecutorAction {
+        let radius = position - center;
+        let tangent = if radius.norm() > 0.0 {
+            Vector2::new(-radius.y, radius.x).normalize()
+        } else {
+            Vector2::x()
+        };
+        let start = position - tangent * spread / 2.0;
+        let end = position + tangent * spread / 2.0;
+
+        let (spawnable, position) = match self {
+            Self::Point => (Spawnable::UnitGroup(units), position),
+            Self::Line => (Spawnable::UnitLine { units, end }, start),
+            Self::Arc => {
+                let angle = if radius.norm() > 0.0 {
+                    spread / radius.norm()
+                } else {
+                    0.0
+                };
+
+                (
+                    Spawnable::UnitArc {
+                        units,
+                        center,
+                        angle,
+                    },
+                    center + Rotation2::new(-angle / 2.0) * radius,
+                )
+            }
+            Self::Split => (
+                Spawnable::UnitSplit {
+                    units,
+                    points: vec![end],
+                },
+                start,
+            ),
+        };
+
+        AttackPlanExecutorAction {
+            spawnable,
+            position,
+            drop_time,
+        }
+    }
+}
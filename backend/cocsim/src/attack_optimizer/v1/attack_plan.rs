@@ -1,14 +1,22 @@
 use arbitrary::Arbitrary;
+use nalgebra::Vector2;
 use rand::{
     Rng,
     seq::IndexedRandom,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     WithCount,
+    WithHousingSpace,
     attack_optimizer::{
         Army,
+        InitialAttackPlan,
         attack_plan_executor::{
             AttackPlanExecutorAction,
             Spawnable,
@@ -18,34 +26,76 @@ use crate::{
             attack_plan_unit_group::AttackPlanUnitGroup,
         },
     },
+    consts::MAX_UNIT_GROUP_SPREAD,
 };
 
-#[derive(Clone, Arbitrary, Debug)]
+#[derive(Serialize, Deserialize, Clone, Arbitrary, Debug)]
 pub struct AttackPlan {
     pub units: Vec<AttackPlanUnitGroup>,
     pub spells: Vec<AttackPlanSpellGroup>,
 }
 
 impl AttackPlan {
-    pub fn new_randomized(army: &Army, rng: &mut impl Rng) -> Self {
+    pub fn new_randomized(army: &Army, config: &GameConfig, rng: &mut impl Rng) -> Self {
         Self {
             units: army
                 .units
                 .iter()
                 .map(|unit| {
-                    AttackPlanUnitGroup::new_randomized(unit.value.clone(), unit.count, rng)
+                    AttackPlanUnitGroup::new_randomized(unit.value.clone(), unit.count, config, rng)
                 })
                 .collect(),
             spells: army
                 .spells
                 .iter()
                 .map(|spell| {
-                    AttackPlanSpellGroup::new_randomized(spell.value.clone(), spell.count, rng)
+                    AttackPlanSpellGroup::new_randomized(
+                        spell.value.clone(),
+                        spell.count,
+                        config,
+                        rng,
+                    )
                 })
                 .collect(),
         }
     }
 
+    /// Random plan with groups present in `initial_plan` placed as in it.
+    pub fn from_initial_plan(
+        initial_plan: &InitialAttackPlan,
+        army: &Army,
+        map: &ValidatedMap,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> anyhow::Result<Self> {
+        let mut result = Self::new_randomized(army, config, rng);
+        let placements = initial_plan.placements(army)?;
+        let (unit_placements, spell_placements) = placements.split_at(army.units.len());
+
+        for (unit_group, placement) in result.units.iter_mut().zip(unit_placements) {
+            let Some(placement) = placement else {
+                continue;
+            };
+
+            unit_group.set_cartesian_position(placement.position, &map.size(), map.drop_zone());
+            unit_group.drop_time = placement.drop_time.clamp(0.0, config.max_unit_drop_time);
+            unit_group.formation = placement.formation;
+            unit_group.spread = placement.spread.clamp(0.0, MAX_UNIT_GROUP_SPREAD);
+        }
+
+        for (spell_group, placement) in result.spells.iter_mut().zip(spell_placements) {
+            let Some(placement) = placement else {
+                continue;
+            };
+
+            spell_group.position =
+                (placement.position / map.size().total_size() as f32).map(|x| x.clamp(0.0, 1.0));
+            spell_group.drop_time = placement.drop_time.clamp(0.0, config.max_unit_drop_time);
+        }
+
+        Ok(result)
+    }
+
     pub fn merge(a: &Self, b: &Self, rng: &mut impl Rng) -> Self {
         let units = a
             .units
@@ -63,38 +113,100 @@ impl AttackPlan {
         Self { units, spells }
     }
 
-    pub fn mutate(&self, rng: &mut impl Rng, temperature: f32) -> Self {
+    pub fn mutate(&self, config: &GameConfig, rng: &mut impl Rng, temperature: f32) -> Self {
         let units = self
             .units
             .iter()
-            .map(|unit| unit.mutate(rng, temperature))
+            .map(|unit| unit.mutate(config, rng, temperature))
             .collect();
         let spells = self
             .spells
             .iter()
-            .map(|spell| spell.mutate(rng, temperature))
+            .map(|spell| spell.mutate(config, rng, temperature))
             .collect();
 
         Self { units, spells }
     }
 
+    /// Replaces count of each unit group with random one (from 0 to count in
+    /// `army`) with `probability`.
+    pub fn mutate_counts(&self, army: &Army, rng: &mut impl Rng, probability: f64) -> Self {
+        let mut result = self.clone();
+
+        for (unit_group, army_unit) in result.units.iter_mut().zip(&army.units) {
+            if rng.random_bool(probability) {
+                unit_group.count = rng.random_range(0..=army_unit.count);
+            }
+        }
+
+        result
+    }
+
+    /// Housing space of units deployed by this plan.
+    pub fn units_housing_space(&self) -> usize {
+        self.units
+            .iter()
+            .map(|unit_group| unit_group.unit_model.housing_space() * unit_group.count)
+            .sum()
+    }
+
+    /// Housing space of spells deployed by this plan.
+    pub fn spells_housing_space(&self) -> usize {
+        self.spells
+            .iter()
+            .map(|spell_group| spell_group.spell_model.housing_space() * spell_group.count)
+            .sum()
+    }
+
+    /// Groups deployed by this plan, empty ones are skipped.
+    pub fn army(&self) -> Army {
+        Army {
+            units: self
+                .units
+                .iter()
+                .filter(|unit_group| unit_group.count > 0)
+                .map(|unit_group| WithCount {
+                    value: unit_group.unit_model.clone(),
+                    count: unit_group.count,
+                })
+                .collect(),
+            spells: self
+                .spells
+                .iter()
+                .filter(|spell_group| spell_group.count > 0)
+                .map(|spell_group| WithCount {
+                    value: spell_group.spell_model.clone(),
+                    count: spell_group.count,
+                })
+                .collect(),
+        }
+    }
+
     pub fn executor_actions(&self, map: &ValidatedMap) -> Vec<AttackPlanExecutorAction> {
         let mut result = Vec::new();
+        let center = Vector2::from_element(map.size().total_size() as f32 / 2.0);
 
-        for unit_group in &self.units {
+        // empty groups aren't in `Self::army`
+        for unit_group in self.units.iter().filter(|unit_group| unit_group.count > 0) {
             let position = unit_group.cartesian_position(&map.size(), map.drop_zone());
 
-            result.push(AttackPlanExecutorAction {
-                spawnable: Spawnable::UnitGroup(WithCount {
+            result.push(unit_group.formation.executor_action(
+                WithCount {
                     value: unit_group.unit_model.clone(),
                     count: unit_group.count,
-                }),
+                },
                 position,
-                drop_time: unit_group.drop_time,
-            });
+                center,
+                unit_group.spread,
+                unit_group.drop_time,
+            ));
         }
 
-        for spell_group in &self.spells {
+        for spell_group in self
+            .spells
+            .iter()
+            .filter(|spell_group| spell_group.count > 0)
+        {
             let position = spell_group.cartesian_position(&map.size());
 
             result.push(AttackPlanExecutorAction {
@@ -7,14 +7,20 @@ use nalgebra::{
     clamp,
 };
 use rand::Rng;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
+    GameConfig,
     consts::MAX_UNIT_DROP_TIME,
     game::features::map_size::MapSize,
     spells::SpellModelEnum,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct AttackPlanSpellGroup {
     pub spell_model: SpellModelEnum,
     pub count: usize,
@@ -24,16 +30,21 @@ pub struct AttackPlanSpellGroup {
 }
 
 impl AttackPlanSpellGroup {
-    pub fn new_randomized(spell_model: SpellModelEnum, count: usize, rng: &mut impl Rng) -> Self {
+    pub fn new_randomized(
+        spell_model: SpellModelEnum,
+        count: usize,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
         Self {
             spell_model,
             count,
             position: Vector2::new(rng.random_range(0.0..=1.0), rng.random_range(0.0..=1.0)),
-            drop_time: rng.random_range(0.0..=MAX_UNIT_DROP_TIME),
+            drop_time: rng.random_range(0.0..=config.max_unit_drop_time),
         }
     }
 
-    pub fn mutate(&self, rng: &mut impl Rng, temperature: f32) -> Self {
+    pub fn mutate(&self, config: &GameConfig, rng: &mut impl Rng, temperature: f32) -> Self {
         Self {
             spell_model: self.spell_model.clone(),
             count: self.count,
@@ -52,7 +63,7 @@ impl AttackPlanSpellGroup {
             drop_time: clamp(
                 self.drop_time + rng.random_range((-0.5)..=0.5) * temperature,
                 0.0,
-                MAX_UNIT_DROP_TIME,
+                config.max_unit_drop_time,
             ),
         }
     }
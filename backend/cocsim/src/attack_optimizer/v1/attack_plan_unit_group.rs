@@ -9,10 +9,22 @@ use nalgebra::{
     Vector2,
     clamp,
 };
-use rand::Rng;
+use rand::{
+    Rng,
+    seq::IndexedRandom,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
-    consts::MAX_UNIT_DROP_TIME,
+    GameConfig,
+    attack_optimizer::UnitGroupFormation,
+    consts::{
+        MAX_UNIT_DROP_TIME,
+        MAX_UNIT_GROUP_SPREAD,
+    },
     game::features::map_size::MapSize,
     geometry::{
         Ray,
@@ -22,7 +34,8 @@ use crate::{
     units::UnitModelEnum,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct AttackPlanUnitGroup {
     pub unit_model: UnitModelEnum,
     pub count: usize,
@@ -31,6 +44,9 @@ pub struct AttackPlanUnitGroup {
     /// from 0 to 1
     pub distance: f32,
     pub drop_time: f32,
+    pub formation: UnitGroupFormation,
+    /// Formation length in tiles, from 0 to [`MAX_UNIT_GROUP_SPREAD`].
+    pub spread: f32,
 }
 
 impl<'a> Arbitrary<'a> for AttackPlanUnitGroup {
@@ -41,22 +57,31 @@ impl<'a> Arbitrary<'a> for AttackPlanUnitGroup {
             angle: (u.int_in_range::<u8>(0u8..=255u8)? as f32) / 255.0 * 2.0 * PI,
             distance: (u.int_in_range::<u8>(0u8..=100u8)? as f32) / 100.0,
             drop_time: (u.int_in_range::<u8>(0u8..=100u8)? as f32) / 100.0 * MAX_UNIT_DROP_TIME,
+            formation: u.arbitrary()?,
+            spread: (u.int_in_range::<u8>(0u8..=100u8)? as f32) / 100.0 * MAX_UNIT_GROUP_SPREAD,
         })
     }
 }
 
 impl AttackPlanUnitGroup {
-    pub fn new_randomized(unit_model: UnitModelEnum, count: usize, rng: &mut impl Rng) -> Self {
+    pub fn new_randomized(
+        unit_model: UnitModelEnum,
+        count: usize,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
         Self {
             unit_model,
             count,
             angle: rng.random_range(0.0..(2.0 * PI)),
             distance: rng.random_range(0.0..=1.0),
-            drop_time: rng.random_range(0.0..=MAX_UNIT_DROP_TIME),
+            drop_time: rng.random_range(0.0..=config.max_unit_drop_time),
+            formation: *UnitGroupFormation::ALL.choose(rng).unwrap(),
+            spread: rng.random_range(0.0..=MAX_UNIT_GROUP_SPREAD),
         }
     }
 
-    pub fn mutate(&self, rng: &mut impl Rng, temperature: f32) -> Self {
+    pub fn mutate(&self, config: &GameConfig, rng: &mut impl Rng, temperature: f32) -> Self {
         Self {
             unit_model: self.unit_model.clone(),
             count: self.count,
@@ -69,7 +94,17 @@ impl AttackPlanUnitGroup {
             drop_time: clamp(
                 self.drop_time + rng.random_range((-0.5)..=0.5) * temperature,
                 0.0,
-                MAX_UNIT_DROP_TIME,
+                config.max_unit_drop_time,
+            ),
+            formation: if rng.random_bool((0.2 * temperature).clamp(0.0, 1.0) as f64) {
+                *UnitGroupFormation::ALL.choose(rng).unwrap()
+            } else {
+                self.formation
+            },
+            spread: clamp(
+                self.spread + rng.random_range((-1.0)..=1.0) * temperature,
+                0.0,
+                MAX_UNIT_GROUP_SPREAD,
             ),
         }
     }
@@ -162,4 +197,40 @@ impl AttackPlanUnitGroup {
 
         start_point + (stop_point - start_point) * clamp(self.distance, 0.01, 0.99) // clamp for unit to not spawn on right or bottom border
     }
+
+    /// Sets `angle` and `distance`, so [`Self::cartesian_position`] is as
+    /// close to `position` as possible.
+    pub fn set_cartesian_position(
+        &mut self,
+        position: Vector2<f32>,
+        map_size: &MapSize,
+        drop_zone: &DMatrix<bool>,
+    ) {
+        let center = Vector2::from_element(map_size.total_size() as f32 / 2.0);
+        let offset = position - center;
+
+        if offset.norm() > 0.0 {
+            self.angle = offset.y.atan2(offset.x);
+        }
+
+        self.distance = 0.0;
+
+        let first = self.cartesian_position(map_size, drop_zone);
+
+        self.distance = 1.0;
+
+        let segment = self.cartesian_position(map_size, drop_zone) - first;
+        let t = if segment.norm_squared() > 0.0 {
+            clamp(
+                (position - first).dot(&segment) / segment.norm_squared(),
+                0.0,
+                1.0,
+            )
+        } else {
+            0.0
+        };
+
+        // inverse of distance clamping in cartesian_position
+        self.distance = 0.01 + 0.98 * t;
+    }
 }
@@ -0,0 +1,186 @@
+use rand::{
+    Rng,
+    seq::IndexedRandom,
+};
+use rand_pcg::Pcg64Mcg;
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    WithMaxHousingSpace,
+    attack_optimizer::{
+        Army,
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        AvailableArmy,
+        attack_plan_executor::AttackPlanExecutorAction,
+        race_attack_plans,
+        v1::AttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        MAX_ARMY_HOUSING_SPACE,
+        MAX_SPELLS_HOUSING_SPACE,
+        RNG_INITIAL_STATE,
+    },
+};
+
+const MERGE_PROBABILITY: f64 = 0.7;
+const MUTATION_TEMPERATURE: f32 = 0.3;
+/// Probability of changing count of each group of new plan.
+const COUNT_MUTATION_PROBABILITY: f64 = 0.2;
+
+/// Genetic optimizer searching army composition together with the plan.
+/// Plan groups are all [`AvailableArmy`] models, their counts are the
+/// composition and always fit housing spaces.
+pub struct CompositionAttackOptimizer {
+    map: ValidatedMap,
+    available_army: AvailableArmy,
+    max_army: Army,
+    config: GameConfig,
+    rng: Pcg64Mcg,
+    /// Sorted by score reversed.
+    population: Vec<(AttackPlan, AttackPlanExecutionStats)>,
+    population_size: usize,
+}
+
+impl CompositionAttackOptimizer {
+    pub fn new(
+        map: ValidatedMap,
+        available_army: AvailableArmy,
+        config: GameConfig,
+        population_size: usize,
+    ) -> anyhow::Result<Self> {
+        available_army.validate()?;
+
+        Ok(Self {
+            map,
+            max_army: available_army.max_army(),
+            available_army,
+            config,
+            rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
+            population: Vec::new(),
+            population_size: population_size.max(2),
+        })
+    }
+
+    pub fn best_plan(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
+        self.population.first()
+    }
+
+    fn new_randomized(&mut self) -> AttackPlan {
+        let plan = AttackPlan::new_randomized(&self.max_army, &self.config, &mut self.rng)
+            .mutate_counts(&self.max_army, &mut self.rng, 1.0);
+
+        self.fit_housing_space(plan)
+    }
+
+    fn new_plan(&mut self) -> AttackPlan {
+        let a = &self.population.choose(&mut self.rng).unwrap().0;
+        let plan = if self.rng.random_bool(MERGE_PROBABILITY) {
+            let b = &self.population.choose(&mut self.rng).unwrap().0;
+
+            AttackPlan::merge(a, b, &mut self.rng)
+        } else {
+            a.clone()
+        };
+        let mut plan = plan
+            .mutate(&self.config, &mut self.rng, MUTATION_TEMPERATURE)
+            .mutate_counts(&self.max_army, &mut self.rng, COUNT_MUTATION_PROBABILITY);
+
+        for (spell_group, army_spell) in plan.spells.iter_mut().zip(&self.max_army.spells) {
+            if self.rng.random_bool(COUNT_MUTATION_PROBABILITY) {
+                spell_group.count = self.rng.random_range(0..=army_spell.count);
+            }
+        }
+
+        self.fit_housing_space(plan)
+    }
+
+    /// Removes random units and spells until plan fits housing spaces.
+    fn fit_housing_space(&mut self, mut plan: AttackPlan) -> AttackPlan {
+        while plan.units_housing_space() > self.available_army.units_housing_space {
+            let deployed = (0..plan.units.len())
+                .filter(|i| plan.units[*i].count > 0)
+                .collect::<Vec<_>>();
+
+            plan.units[*deployed.choose(&mut self.rng).unwrap()].count -= 1;
+        }
+
+        while plan.spells_housing_space() > self.available_army.spells_housing_space {
+            let deployed = (0..plan.spells.len())
+                .filter(|i| plan.spells[*i].count > 0)
+                .collect::<Vec<_>>();
+
+            plan.spells[*deployed.choose(&mut self.rng).unwrap()].count -= 1;
+        }
+
+        plan
+    }
+}
+
+impl AttackOptimizer for CompositionAttackOptimizer {
+    fn step(&mut self) {
+        let new_plans = if self.population.is_empty() {
+            (0..self.population_size)
+                .map(|_| self.new_randomized())
+                .collect::<Vec<_>>()
+        } else {
+            (0..self.population_size)
+                .map(|_| self.new_plan())
+                .collect::<Vec<_>>()
+        };
+        let new_plans_actions = new_plans
+            .iter()
+            .map(|plan| plan.executor_actions(&self.map))
+            .collect::<Vec<_>>();
+        // new plan is useful only if it's better than the worst survivor
+        let incumbent = if self.population.len() < self.population_size {
+            None
+        } else {
+            self.population.last().map(|(_, stats)| stats)
+        };
+        let new_plans_stats = race_attack_plans(
+            &self.map,
+            &self.config,
+            &new_plans_actions,
+            incumbent,
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        );
+
+        // plans dropped by racing have less samples
+        self.population.extend(
+            new_plans
+                .into_iter()
+                .zip(new_plans_stats)
+                .filter(|(_, stats)| stats.samples_count == ATTACK_PLAN_EXECUTIONS_COUNT),
+        );
+
+        // sort by score reversed, so highest score will be at index 0
+        self.population
+            .sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+        self.population.truncate(self.population_size);
+    }
+
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best_plan()
+            .map(|(plan, stats)| (plan.executor_actions(&self.map), stats.clone()))
+    }
+
+    fn best_army(&self) -> Option<Army> {
+        self.best_plan().map(|(plan, _)| {
+            let army = plan.army();
+            let units = WithMaxHousingSpace::<MAX_ARMY_HOUSING_SPACE, _>::new(&army.units)
+                .expect("Composition should fit housing space");
+            let spells = WithMaxHousingSpace::<MAX_SPELLS_HOUSING_SPACE, _>::new(&army.spells)
+                .expect("Composition should fit housing space");
+
+            Army {
+                units: units.to_vec(),
+                spells: spells.to_vec(),
+            }
+        })
+    }
+}
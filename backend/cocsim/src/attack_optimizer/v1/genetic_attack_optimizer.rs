@@ -9,15 +9,15 @@ use rand::{
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
         AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
         execute_attack_plan,
-        v1::{
-            AttackOptimizer,
-            AttackPlan,
-        },
+        v1::AttackPlan,
     },
     consts::{
         ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -32,6 +32,7 @@ use crate::{
 pub struct GeneticAttackOptimizer {
     map: ValidatedMap,
     army: Army,
+    config: GameConfig,
     rng: Pcg64Mcg,
     population: Vec<(AttackPlan, AttackPlanExecutionStats)>,
     pub mutation_temperature: f32,
@@ -44,34 +45,43 @@ impl GeneticAttackOptimizer {
     pub fn new(
         map: ValidatedMap,
         army: Army,
+        config: GameConfig,
+        initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
         mutation_temperature_decay: f32,
         merge_probability_decay: f64,
     ) -> Self {
         Self {
             map,
             army,
+            config,
             rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
-            population: Vec::new(),
+            population: initial_plan.into_iter().collect(),
             mutation_temperature: 1.0,
             mutation_temperature_decay,
             merge_probability: 1.0,
             merge_probability_decay,
         }
     }
+
+    pub fn best_plan(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
+        self.population.first()
+    }
 }
 
 impl AttackOptimizer for GeneticAttackOptimizer {
-    fn best(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
-        self.population.first()
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best_plan()
+            .map(|(plan, stats)| (plan.executor_actions(&self.map), stats.clone()))
     }
 
-    fn step(&mut self) -> &(AttackPlan, AttackPlanExecutionStats) {
+    fn step(&mut self) {
         let mut new_population = Vec::new();
 
         while new_population.len() != NEW_RANDOM_PLANS {
-            let new_plan = AttackPlan::new_randomized(&self.army, &mut self.rng);
+            let new_plan = AttackPlan::new_randomized(&self.army, &self.config, &mut self.rng);
             let new_plan_stats = execute_attack_plan(
                 &self.map,
+                &self.config,
                 &new_plan.executor_actions(&self.map),
                 ATTACK_PLAN_EXECUTOR_TPS,
                 ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -84,22 +94,25 @@ impl AttackOptimizer for GeneticAttackOptimizer {
             new_population.push(self.population[0].clone());
 
             while new_population.len() != NEW_POPULATION_SIZE {
-                let new_plan = if self.rng.random_bool(self.merge_probability) {
-                    let [a_index, b_index] =
-                        sample_array(&mut self.rng, self.population.len()).unwrap();
-
-                    AttackPlan::merge(
-                        &self.population[a_index].0,
-                        &self.population[b_index].0,
-                        &mut self.rng,
-                    )
-                } else {
-                    self.population.choose(&mut self.rng).unwrap().0.clone()
-                };
-
-                let new_plan = new_plan.mutate(&mut self.rng, self.mutation_temperature);
+                let new_plan =
+                    if self.population.len() > 1 && self.rng.random_bool(self.merge_probability) {
+                        let [a_index, b_index] =
+                            sample_array(&mut self.rng, self.population.len()).unwrap();
+
+                        AttackPlan::merge(
+                            &self.population[a_index].0,
+                            &self.population[b_index].0,
+                            &mut self.rng,
+                        )
+                    } else {
+                        self.population.choose(&mut self.rng).unwrap().0.clone()
+                    };
+
+                let new_plan =
+                    new_plan.mutate(&self.config, &mut self.rng, self.mutation_temperature);
                 let new_plan_stats = execute_attack_plan(
                     &self.map,
+                    &self.config,
                     &new_plan.executor_actions(&self.map),
                     ATTACK_PLAN_EXECUTOR_TPS,
                     ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -126,7 +139,5 @@ impl AttackOptimizer for GeneticAttackOptimizer {
             0.0,
             1.0,
         );
-
-        &self.population[0]
     }
 }
@@ -0,0 +1,382 @@
+use rand::Rng;
+use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan_seeds,
+        v1::AttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        RNG_INITIAL_STATE,
+    },
+};
+
+const MERGE_PROBABILITY: f64 = 0.9;
+const MUTATION_TEMPERATURE: f32 = 0.5;
+/// Probability of changing count of each unit group of new plan.
+const COUNT_MUTATION_PROBABILITY: f64 = 0.1;
+/// Used for initial random plans, so population starts with different
+/// housing space.
+const INITIAL_COUNT_MUTATION_PROBABILITY: f64 = 0.5;
+
+/// Objectives optimized by [`ParetoAttackOptimizer`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParetoObjectives {
+    /// Maximized.
+    pub avg_percentage_destroyed: f32,
+    /// Minimized.
+    pub avg_time_elapsed: f32,
+    /// Housing space of units in plan, minimized. Approximates housing space
+    /// actually deployed: groups with drop time after attack has ended are
+    /// counted too.
+    pub housing_space: usize,
+}
+
+impl ParetoObjectives {
+    pub fn new(plan: &AttackPlan, stats: &AttackPlanExecutionStats) -> Self {
+        Self {
+            avg_percentage_destroyed: stats.avg_percentage_destroyed,
+            avg_time_elapsed: stats.avg_time_elapsed,
+            housing_space: plan.units_housing_space(),
+        }
+    }
+
+    /// Not worse in any objective and better in at least one.
+    pub fn dominates(&self, other: &Self) -> bool {
+        let not_worse = self.avg_percentage_destroyed >= other.avg_percentage_destroyed
+            && self.avg_time_elapsed <= other.avg_time_elapsed
+            && self.housing_space <= other.housing_space;
+        let better = self.avg_percentage_destroyed > other.avg_percentage_destroyed
+            || self.avg_time_elapsed < other.avg_time_elapsed
+            || self.housing_space < other.housing_space;
+
+        not_worse && better
+    }
+
+    fn values(&self) -> [f32; 3] {
+        [
+            self.avg_percentage_destroyed,
+            self.avg_time_elapsed,
+            self.housing_space as f32,
+        ]
+    }
+}
+
+/// Plan of Pareto front, see [`AttackOptimizer::pareto_front`].
+#[derive(Clone)]
+pub struct ParetoPlan {
+    /// Groups deployed by plan, they can be smaller than groups of army.
+    pub army: Army,
+    pub actions: Vec<AttackPlanExecutorAction>,
+    pub stats: AttackPlanExecutionStats,
+    pub objectives: ParetoObjectives,
+}
+
+#[derive(Clone)]
+struct Individual {
+    plan: AttackPlan,
+    stats: AttackPlanExecutionStats,
+    objectives: ParetoObjectives,
+    /// Index of non-dominated front, 0 is Pareto front.
+    rank: usize,
+    crowding_distance: f32,
+}
+
+/// NSGA-II on [`AttackPlan`]. Unit groups may be deployed partially, so it
+/// finds plans using less troops (e.g. to keep the rest for the second
+/// wave). [`AttackOptimizer::best`] returns plan with the best score, the
+/// whole front is returned by [`AttackOptimizer::pareto_front`].
+pub struct ParetoAttackOptimizer {
+    map: ValidatedMap,
+    army: Army,
+    config: GameConfig,
+    rng: Pcg64Mcg,
+    population: Vec<Individual>,
+    population_size: usize,
+}
+
+impl ParetoAttackOptimizer {
+    pub fn new(
+        map: ValidatedMap,
+        army: Army,
+        config: GameConfig,
+        initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+        population_size: usize,
+    ) -> Self {
+        let population = initial_plan
+            .into_iter()
+            .map(|(plan, stats)| Individual {
+                objectives: ParetoObjectives::new(&plan, &stats),
+                plan,
+                stats,
+                rank: 0,
+                crowding_distance: f32::INFINITY,
+            })
+            .collect();
+
+        Self {
+            map,
+            army,
+            config,
+            rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
+            population,
+            population_size: population_size.max(2),
+        }
+    }
+
+    fn evaluate(&self, plans: Vec<AttackPlan>) -> Vec<Individual> {
+        let actions = plans
+            .iter()
+            .map(|plan| plan.executor_actions(&self.map))
+            .collect::<Vec<_>>();
+        let seeds = actions
+            .iter()
+            .flat_map(|actions| {
+                (0..ATTACK_PLAN_EXECUTIONS_COUNT).map(move |i| (actions.as_slice(), i))
+            })
+            .collect::<Vec<_>>();
+        let mut executions = execute_attack_plan_seeds(
+            &self.map,
+            &self.config,
+            &seeds,
+            ATTACK_PLAN_EXECUTOR_TPS,
+            None,
+        )
+        .into_iter();
+
+        plans
+            .into_iter()
+            .map(|plan| {
+                let stats = AttackPlanExecutionStats::new(
+                    executions
+                        .by_ref()
+                        .take(ATTACK_PLAN_EXECUTIONS_COUNT)
+                        .flatten()
+                        .collect(),
+                    &self.config,
+                );
+
+                Individual {
+                    objectives: ParetoObjectives::new(&plan, &stats),
+                    plan,
+                    stats,
+                    rank: 0,
+                    crowding_distance: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    /// Binary tournament by rank, then by crowding distance.
+    fn select(&mut self) -> &AttackPlan {
+        let a = &self.population[self.rng.random_range(0..self.population.len())];
+        let b = &self.population[self.rng.random_range(0..self.population.len())];
+
+        if (a.rank, -a.crowding_distance) <= (b.rank, -b.crowding_distance) {
+            &a.plan
+        } else {
+            &b.plan
+        }
+    }
+
+    fn new_plan(&mut self) -> AttackPlan {
+        let a = self.select().clone();
+        let plan = if self.rng.random_bool(MERGE_PROBABILITY) {
+            let b = self.select().clone();
+
+            AttackPlan::merge(&a, &b, &mut self.rng)
+        } else {
+            a
+        };
+
+        plan.mutate(&self.config, &mut self.rng, MUTATION_TEMPERATURE)
+            .mutate_counts(&self.army, &mut self.rng, COUNT_MUTATION_PROBABILITY)
+    }
+}
+
+impl AttackOptimizer for ParetoAttackOptimizer {
+    fn step(&mut self) {
+        let new_plans = if self.population.len() < 2 {
+            (self.population.len()..self.population_size)
+                .map(|_| {
+                    AttackPlan::new_randomized(&self.army, &self.config, &mut self.rng)
+                        .mutate_counts(
+                            &self.army,
+                            &mut self.rng,
+                            INITIAL_COUNT_MUTATION_PROBABILITY,
+                        )
+                })
+                .collect::<Vec<_>>()
+        } else {
+            (0..self.population_size)
+                .map(|_| self.new_plan())
+                .collect::<Vec<_>>()
+        };
+
+        let mut population = std::mem::take(&mut self.population);
+
+        population.extend(self.evaluate(new_plans));
+
+        // copies of the same plan would take the whole front
+        let mut seen_objectives = Vec::with_capacity(population.len());
+
+        population.retain(|individual| {
+            if seen_objectives.contains(&individual.objectives) {
+                false
+            } else {
+                seen_objectives.push(individual.objectives.clone());
+
+                true
+            }
+        });
+
+        self.population = select_survivors(population, self.population_size);
+    }
+
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.population
+            .iter()
+            .max_by(|a, b| a.stats.score.total_cmp(&b.stats.score))
+            .map(|individual| {
+                (
+                    individual.plan.executor_actions(&self.map),
+                    individual.stats.clone(),
+                )
+            })
+    }
+
+    /// Non-dominated plans, sorted by housing space.
+    fn pareto_front(&self) -> Vec<ParetoPlan> {
+        let mut result = self
+            .population
+            .iter()
+            .filter(|individual| individual.rank == 0)
+            .map(|individual| ParetoPlan {
+                army: individual.plan.army(),
+                actions: individual.plan.executor_actions(&self.map),
+                stats: individual.stats.clone(),
+                objectives: individual.objectives.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        result.sort_by_key(|plan| plan.objectives.housing_space);
+
+        result
+    }
+}
+
+/// Assigns ranks and crowding distances, keeps `count` best individuals.
+fn select_survivors(population: Vec<Individual>, count: usize) -> Vec<Individual> {
+    let mut result = Vec::with_capacity(count);
+
+    for mut front in non_dominated_fronts(population) {
+        assign_crowding_distances(&mut front);
+
+        if result.len() + front.len() > count {
+            front.sort_by(|a, b| b.crowding_distance.total_cmp(&a.crowding_distance));
+            front.truncate(count - result.len());
+        }
+
+        result.extend(front);
+
+        if result.len() == count {
+            break;
+        }
+    }
+
+    result
+}
+
+fn non_dominated_fronts(population: Vec<Individual>) -> Vec<Vec<Individual>> {
+    let len = population.len();
+    // dominated[i] are individuals dominated by i-th
+    let mut dominated = vec![Vec::new(); len];
+    let mut domination_count = vec![0; len];
+
+    for i in 0..len {
+        for j in 0..len {
+            if population[i]
+                .objectives
+                .dominates(&population[j].objectives)
+            {
+                dominated[i].push(j);
+                domination_count[j] += 1;
+            }
+        }
+    }
+
+    let mut ranks = vec![0; len];
+    let mut current = (0..len)
+        .filter(|i| domination_count[*i] == 0)
+        .collect::<Vec<_>>();
+    let mut rank = 0;
+
+    while !current.is_empty() {
+        let mut next = Vec::new();
+
+        for &i in &current {
+            ranks[i] = rank;
+
+            for &j in &dominated[i] {
+                domination_count[j] -= 1;
+
+                if domination_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+
+        current = next;
+        rank += 1;
+    }
+
+    let mut fronts = vec![Vec::new(); rank];
+
+    for (mut individual, rank) in population.into_iter().zip(ranks) {
+        individual.rank = rank;
+        fronts[rank].push(individual);
+    }
+
+    fronts
+}
+
+fn assign_crowding_distances(front: &mut [Individual]) {
+    for individual in front.iter_mut() {
+        individual.crowding_distance = 0.0;
+    }
+
+    for objective in 0..3 {
+        front.sort_by(|a, b| {
+            a.objectives.values()[objective].total_cmp(&b.objectives.values()[objective])
+        });
+
+        let min = front[0].objectives.values()[objective];
+        let max = front[front.len() - 1].objectives.values()[objective];
+
+        front[0].crowding_distance = f32::INFINITY;
+        front[front.len() - 1].crowding_distance = f32::INFINITY;
+
+        if max <= min {
+            continue;
+        }
+
+        for i in 1..front.len() - 1 {
+            front[i].crowding_distance += (front[i + 1].objectives.values()[objective]
+                - front[i - 1].objectives.values()[objective])
+                / (max - min);
+        }
+    }
+}
@@ -1,15 +1,22 @@
+use anyhow::bail;
 use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
+        AttackOptimizerState,
         AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
         execute_attack_plan,
-        v1::{
-            AttackOptimizer,
-            AttackPlan,
-        },
+        race_attack_plans,
+        v1::AttackPlan,
     },
     consts::{
         ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -18,30 +25,48 @@ use crate::{
     },
 };
 
+/// Serializable state of [`RandomAttackOptimizer`], without its inputs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomAttackOptimizerCheckpoint {
+    rng: Pcg64Mcg,
+    plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+    plans_per_step: usize,
+}
+
 pub struct RandomAttackOptimizer {
     map: ValidatedMap,
     army: Army,
+    config: GameConfig,
     rng: Pcg64Mcg,
     plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
     plans_per_step: usize,
 }
 
 impl RandomAttackOptimizer {
-    pub fn new(map: ValidatedMap, army: Army, plans_per_step: usize) -> Self {
+    pub fn new(
+        map: ValidatedMap,
+        army: Army,
+        config: GameConfig,
+        initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+        plans_per_step: usize,
+    ) -> Self {
         Self {
             map,
             army,
+            config,
             rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
-            plan: None,
+            plan: initial_plan,
             plans_per_step,
         }
     }
 
     fn init_plan(&mut self) {
         if self.plan.is_none() {
-            let plan = AttackPlan::new_randomized(&self.army, &mut self.rng);
+            let plan = AttackPlan::new_randomized(&self.army, &self.config, &mut self.rng);
             let stats = execute_attack_plan(
                 &self.map,
+                &self.config,
                 &plan.executor_actions(&self.map),
                 ATTACK_PLAN_EXECUTIONS_COUNT,
                 ATTACK_PLAN_EXECUTOR_TPS,
@@ -50,35 +75,70 @@ impl RandomAttackOptimizer {
             self.plan = Some((plan, stats));
         }
     }
+
+    pub fn best_plan(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
+        self.plan.as_ref()
+    }
 }
 
 impl AttackOptimizer for RandomAttackOptimizer {
-    fn best(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
-        self.plan.as_ref()
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best_plan()
+            .map(|(plan, stats)| (plan.executor_actions(&self.map), stats.clone()))
     }
 
-    fn step(&mut self) -> &(AttackPlan, AttackPlanExecutionStats) {
+    fn step(&mut self) {
         if self.plan.is_none() {
             self.init_plan();
         }
 
         let (plan, stats) = self.plan.as_mut().unwrap();
+        let new_plans = (0..self.plans_per_step)
+            .map(|_| AttackPlan::new_randomized(&self.army, &self.config, &mut self.rng))
+            .collect::<Vec<_>>();
+        let new_plans_actions = new_plans
+            .iter()
+            .map(|new_plan| new_plan.executor_actions(&self.map))
+            .collect::<Vec<_>>();
+        let new_plans_stats = race_attack_plans(
+            &self.map,
+            &self.config,
+            &new_plans_actions,
+            Some(stats),
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        );
 
-        for _ in 0..self.plans_per_step {
-            let new_plan = AttackPlan::new_randomized(&self.army, &mut self.rng);
-            let new_stats = execute_attack_plan(
-                &self.map,
-                &new_plan.executor_actions(&self.map),
-                ATTACK_PLAN_EXECUTIONS_COUNT,
-                ATTACK_PLAN_EXECUTOR_TPS,
-            );
-
-            if new_stats.score >= stats.score {
+        for (new_plan, new_stats) in new_plans.into_iter().zip(new_plans_stats) {
+            // plans dropped by racing have less samples
+            if new_stats.samples_count == ATTACK_PLAN_EXECUTIONS_COUNT
+                && new_stats.score >= stats.score
+            {
                 *stats = new_stats;
                 *plan = new_plan;
             }
         }
+    }
+
+    fn state(&self) -> Option<AttackOptimizerState> {
+        Some(AttackOptimizerState::V1Random(
+            RandomAttackOptimizerCheckpoint {
+                rng: self.rng.clone(),
+                plan: self.plan.clone(),
+                plans_per_step: self.plans_per_step,
+            },
+        ))
+    }
+
+    fn restore(&mut self, state: AttackOptimizerState) -> anyhow::Result<()> {
+        let AttackOptimizerState::V1Random(checkpoint) = state else {
+            bail!("Attack optimizer state is of another optimizer");
+        };
+
+        self.rng = checkpoint.rng;
+        self.plan = checkpoint.plan;
+        self.plans_per_step = checkpoint.plans_per_step;
 
-        self.plan.as_ref().unwrap()
+        Ok(())
     }
 }
@@ -1,15 +1,21 @@
+use anyhow::bail;
 use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
+        AttackOptimizerState,
         AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
         execute_attack_plan,
-        v1::{
-            AttackOptimizer,
-            AttackPlan,
-        },
+        v1::AttackPlan,
     },
     consts::{
         ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -18,9 +24,22 @@ use crate::{
     },
 };
 
+/// Serializable state of [`SimulatedAnnealingAttackOptimizer`], without its
+/// inputs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedAnnealingAttackOptimizerCheckpoint {
+    rng: Pcg64Mcg,
+    plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+    iterations_per_step: usize,
+    iterations: usize,
+    current_iteration: usize,
+}
+
 pub struct SimulatedAnnealingAttackOptimizer {
     map: ValidatedMap,
     army: Army,
+    config: GameConfig,
     rng: Pcg64Mcg,
     plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
     iterations_per_step: usize,
@@ -32,6 +51,7 @@ impl SimulatedAnnealingAttackOptimizer {
     pub fn new(
         map: ValidatedMap,
         army: Army,
+        config: GameConfig,
         initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
         iterations: usize,
         iterations_per_step: usize,
@@ -39,6 +59,7 @@ impl SimulatedAnnealingAttackOptimizer {
         Self {
             map,
             army,
+            config,
             rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
             plan: initial_plan,
             iterations,
@@ -49,9 +70,10 @@ impl SimulatedAnnealingAttackOptimizer {
 
     fn init_plan(&mut self) {
         if self.plan.is_none() {
-            let plan = AttackPlan::new_randomized(&self.army, &mut self.rng);
+            let plan = AttackPlan::new_randomized(&self.army, &self.config, &mut self.rng);
             let stats = execute_attack_plan(
                 &self.map,
+                &self.config,
                 &plan.executor_actions(&self.map),
                 ATTACK_PLAN_EXECUTIONS_COUNT,
                 ATTACK_PLAN_EXECUTOR_TPS,
@@ -60,14 +82,19 @@ impl SimulatedAnnealingAttackOptimizer {
             self.plan = Some((plan, stats));
         }
     }
+
+    pub fn best_plan(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
+        self.plan.as_ref()
+    }
 }
 
 impl AttackOptimizer for SimulatedAnnealingAttackOptimizer {
-    fn best(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
-        self.plan.as_ref()
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best_plan()
+            .map(|(plan, stats)| (plan.executor_actions(&self.map), stats.clone()))
     }
 
-    fn step(&mut self) -> &(AttackPlan, AttackPlanExecutionStats) {
+    fn step(&mut self) {
         if self.plan.is_none() {
             self.init_plan();
         }
@@ -84,15 +111,16 @@ impl AttackOptimizer for SimulatedAnnealingAttackOptimizer {
             let temperature = 1.0 - self.current_iteration as f32 / self.iterations as f32;
 
             for unit in &mut new_plan.units {
-                *unit = unit.mutate(&mut self.rng, temperature);
+                *unit = unit.mutate(&self.config, &mut self.rng, temperature);
             }
 
             for spell in &mut new_plan.spells {
-                *spell = spell.mutate(&mut self.rng, temperature);
+                *spell = spell.mutate(&self.config, &mut self.rng, temperature);
             }
 
             let new_stats = execute_attack_plan(
                 &self.map,
+                &self.config,
                 &new_plan.executor_actions(&self.map),
                 ATTACK_PLAN_EXECUTIONS_COUNT,
                 ATTACK_PLAN_EXECUTOR_TPS,
@@ -105,7 +133,31 @@ impl AttackOptimizer for SimulatedAnnealingAttackOptimizer {
 
             self.current_iteration += 1;
         }
+    }
+
+    fn state(&self) -> Option<AttackOptimizerState> {
+        Some(AttackOptimizerState::V1SimulatedAnnealing(
+            SimulatedAnnealingAttackOptimizerCheckpoint {
+                rng: self.rng.clone(),
+                plan: self.plan.clone(),
+                iterations_per_step: self.iterations_per_step,
+                iterations: self.iterations,
+                current_iteration: self.current_iteration,
+            },
+        ))
+    }
+
+    fn restore(&mut self, state: AttackOptimizerState) -> anyhow::Result<()> {
+        let AttackOptimizerState::V1SimulatedAnnealing(checkpoint) = state else {
+            bail!("Attack optimizer state is of another optimizer");
+        };
+
+        self.rng = checkpoint.rng;
+        self.plan = checkpoint.plan;
+        self.iterations_per_step = checkpoint.iterations_per_step;
+        self.iterations = checkpoint.iterations;
+        self.current_iteration = checkpoint.current_iteration;
 
-        self.plan.as_ref().unwrap()
+        Ok(())
     }
 }
@@ -5,15 +5,19 @@ use rand::{
 };
 
 use crate::{
+    DropZonePolygon,
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        InitialAttackPlan,
+        UnitGroupFormation,
         attack_plan_executor::{
             AttackPlanExecutorAction,
             Spawnable,
         },
     },
-    consts::MAX_UNIT_DROP_TIME,
+    consts::MAX_UNIT_GROUP_SPREAD,
 };
 
 const POSITION_QUANTS_PER_TILE: i32 = 2;
@@ -26,15 +30,38 @@ pub struct AttackPlanPosition {
 }
 
 impl AttackPlanPosition {
+    /// Random position. If restricted to drop zone, it's sampled along drop
+    /// zone edge, where deployment is closest to buildings.
     pub fn new_randomized(
         map: &ValidatedMap,
         restricted_to_drop_zone: bool,
         rng: &mut impl Rng,
     ) -> Self {
+        let polygons = map.drop_zone_polygons();
+
+        if restricted_to_drop_zone && !polygons.is_empty() {
+            let total_perimeter = polygons.iter().map(DropZonePolygon::perimeter).sum::<f32>();
+            let mut distance = rng.random_range(0.0..total_perimeter);
+            let mut polygons = polygons.iter();
+            // last polygon takes float rounding remainder
+            let mut polygon = polygons.next().unwrap();
+
+            for next_polygon in polygons {
+                if distance < polygon.perimeter() {
+                    break;
+                }
+
+                distance -= polygon.perimeter();
+                polygon = next_polygon;
+            }
+
+            return Self::from_position(map, true, polygon.point_at(distance));
+        }
+
         let tile = if restricted_to_drop_zone {
             *map.drop_zone_free_tiles()
                 .choose(rng)
-                .expect("At least one free tile expected")
+                .expect("Attack optimizer checks that drop zone isn't empty")
         } else {
             (
                 rng.random_range(0..map.size().total_size()) as usize,
@@ -51,6 +78,30 @@ impl AttackPlanPosition {
         }
     }
 
+    /// Quantized `position`. If restricted to drop zone, nearest drop zone
+    /// point is used.
+    pub fn from_position(
+        map: &ValidatedMap,
+        restricted_to_drop_zone: bool,
+        position: Vector2<f32>,
+    ) -> Self {
+        let position = if restricted_to_drop_zone {
+            map.nearest_drop_zone_point(position)
+                .expect("Attack optimizer checks that drop zone isn't empty")
+        } else {
+            position
+        };
+        let max = map.size().total_size() * POSITION_QUANTS_PER_TILE - 1;
+        let quantized =
+            (position * POSITION_QUANTS_PER_TILE as f32).map(|x| (x.floor() as i32).clamp(0, max));
+
+        Self {
+            restricted_to_drop_zone,
+            x: quantized.x,
+            y: quantized.y,
+        }
+    }
+
     fn neighbors(&self, map: &ValidatedMap, radius: usize) -> Vec<Self> {
         let radius = radius as i32;
 
@@ -91,17 +142,23 @@ const TIME_QUANTS_PER_SECOND: i32 = 2;
 pub struct AttackPlanTime(i32);
 
 impl AttackPlanTime {
-    fn new_randomized(rng: &mut impl Rng) -> Self {
-        Self(rng.random_range(0..=(TIME_QUANTS_PER_SECOND * MAX_UNIT_DROP_TIME as i32)))
+    fn new_randomized(config: &GameConfig, rng: &mut impl Rng) -> Self {
+        Self(rng.random_range(0..=Self::max(config)))
+    }
+
+    /// Nearest quantized time.
+    pub fn from_time(config: &GameConfig, time: f32) -> Self {
+        Self(((time * TIME_QUANTS_PER_SECOND as f32).round() as i32).clamp(0, Self::max(config)))
     }
 
-    fn neighbors(&self, radius: usize) -> Vec<Self> {
+    fn neighbors(&self, config: &GameConfig, radius: usize) -> Vec<Self> {
         let radius = radius as i32;
+        let max = Self::max(config);
 
         let mut result = Vec::new();
 
         for time in (self.0 - radius)..=(self.0 + radius) {
-            if time >= 0 && time <= (TIME_QUANTS_PER_SECOND * MAX_UNIT_DROP_TIME as i32) {
+            if time >= 0 && time <= max {
                 result.push(Self(time));
             }
         }
@@ -112,36 +169,133 @@ impl AttackPlanTime {
     pub fn to_time(&self) -> f32 {
         self.0 as f32 / TIME_QUANTS_PER_SECOND as f32
     }
+
+    fn max(config: &GameConfig) -> i32 {
+        TIME_QUANTS_PER_SECOND * config.max_unit_drop_time as i32
+    }
+}
+
+const FORMATION_CHANGE_PROBABILITY: f64 = 0.1;
+
+/// Spread is in whole tiles.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AttackPlanFormation {
+    formation: UnitGroupFormation,
+    spread: i32,
+}
+
+impl AttackPlanFormation {
+    fn new_randomized(rng: &mut impl Rng) -> Self {
+        Self {
+            formation: *UnitGroupFormation::ALL.choose(rng).unwrap(),
+            spread: rng.random_range(0..=Self::max_spread()),
+        }
+    }
+
+    /// `spread` is rounded to whole tiles.
+    pub fn new(formation: UnitGroupFormation, spread: f32) -> Self {
+        Self {
+            formation,
+            spread: (spread.round() as i32).clamp(0, Self::max_spread()),
+        }
+    }
+
+    /// Neighbor with spread within `radius`. Formation is changed only with
+    /// [`FORMATION_CHANGE_PROBABILITY`], so neighborhood stays local.
+    fn random_neighbor(&self, radius: usize, rng: &mut impl Rng) -> Self {
+        let radius = radius as i32;
+        let formation = if rng.random_bool(FORMATION_CHANGE_PROBABILITY) {
+            *UnitGroupFormation::ALL.choose(rng).unwrap()
+        } else {
+            self.formation
+        };
+
+        Self {
+            formation,
+            spread: rng.random_range(
+                (self.spread - radius).max(0)..=(self.spread + radius).min(Self::max_spread()),
+            ),
+        }
+    }
+
+    fn max_spread() -> i32 {
+        MAX_UNIT_GROUP_SPREAD as i32
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub struct AttackPlan {
     pub positions: Vec<AttackPlanPosition>,
     pub drop_times: Vec<AttackPlanTime>,
+    /// One for each unit group.
+    pub formations: Vec<AttackPlanFormation>,
 }
 
 impl AttackPlan {
-    pub fn new_randomized(army: &Army, map: &ValidatedMap, rng: &mut impl Rng) -> Self {
+    pub fn new_randomized(
+        army: &Army,
+        map: &ValidatedMap,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
         let mut positions = Vec::new();
         let mut drop_times = Vec::new();
+        let mut formations = Vec::new();
 
         for _unit in &army.units {
             positions.push(AttackPlanPosition::new_randomized(map, true, rng));
-            drop_times.push(AttackPlanTime::new_randomized(rng));
+            drop_times.push(AttackPlanTime::new_randomized(config, rng));
+            formations.push(AttackPlanFormation::new_randomized(rng));
         }
 
         for _spell in &army.spells {
             positions.push(AttackPlanPosition::new_randomized(map, false, rng));
-            drop_times.push(AttackPlanTime::new_randomized(rng));
+            drop_times.push(AttackPlanTime::new_randomized(config, rng));
         }
 
         Self {
             positions,
             drop_times,
+            formations,
         }
     }
 
-    pub fn random_neighbor(&self, map: &ValidatedMap, radius: usize, rng: &mut impl Rng) -> Self {
+    /// Random plan with groups present in `initial_plan` placed as in it.
+    pub fn from_initial_plan(
+        initial_plan: &InitialAttackPlan,
+        army: &Army,
+        map: &ValidatedMap,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> anyhow::Result<Self> {
+        let mut result = Self::new_randomized(army, map, config, rng);
+
+        for (i, placement) in initial_plan.placements(army)?.into_iter().enumerate() {
+            let Some(placement) = placement else {
+                continue;
+            };
+            let is_unit = i < army.units.len();
+
+            result.positions[i] =
+                AttackPlanPosition::from_position(map, is_unit, placement.position);
+            result.drop_times[i] = AttackPlanTime::from_time(config, placement.drop_time);
+
+            if is_unit {
+                result.formations[i] =
+                    AttackPlanFormation::new(placement.formation, placement.spread);
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn random_neighbor(
+        &self,
+        map: &ValidatedMap,
+        config: &GameConfig,
+        radius: usize,
+        rng: &mut impl Rng,
+    ) -> Self {
         let positions_variants = self
             .positions
             .iter()
@@ -150,7 +304,7 @@ impl AttackPlan {
         let drop_times_variants = self
             .drop_times
             .iter()
-            .map(|t| t.neighbors(radius))
+            .map(|t| t.neighbors(config, radius))
             .collect::<Vec<_>>();
 
         let positions = positions_variants
@@ -161,25 +315,38 @@ impl AttackPlan {
             .iter()
             .map(|variants| variants.choose(rng).unwrap().to_owned())
             .collect::<Vec<_>>();
+        let formations = self
+            .formations
+            .iter()
+            .map(|f| f.random_neighbor(radius, rng))
+            .collect::<Vec<_>>();
 
         Self {
             positions,
             drop_times,
+            formations,
         }
     }
 
-    pub fn executor_actions(&self, army: &Army) -> Vec<AttackPlanExecutorAction> {
+    pub fn executor_actions(
+        &self,
+        army: &Army,
+        map: &ValidatedMap,
+    ) -> Vec<AttackPlanExecutorAction> {
         let mut result = Vec::new();
+        let center = Vector2::from_element(map.size().total_size() as f32 / 2.0);
 
         let mut positions_iter = self.positions.iter();
         let mut drop_times_iter = self.drop_times.iter();
 
-        for unit in &army.units {
-            result.push(AttackPlanExecutorAction {
-                spawnable: Spawnable::UnitGroup(unit.clone()),
-                position: positions_iter.next().unwrap().to_position(),
-                drop_time: drop_times_iter.next().unwrap().to_time(),
-            });
+        for (unit, formation) in army.units.iter().zip(&self.formations) {
+            result.push(formation.formation.executor_action(
+                unit.clone(),
+                positions_iter.next().unwrap().to_position(),
+                center,
+                formation.spread as f32,
+                drop_times_iter.next().unwrap().to_time(),
+            ));
         }
 
         for spell in &army.spells {
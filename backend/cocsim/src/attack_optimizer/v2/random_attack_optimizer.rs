@@ -3,11 +3,14 @@ use std::collections::HashMap;
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
         AttackPlanExecutionStats,
-        execute_attack_plan,
+        attack_plan_executor::AttackPlanExecutorAction,
+        race_attack_plans,
         v2::AttackPlan,
     },
     consts::{
@@ -20,48 +23,83 @@ use crate::{
 pub struct RandomAttackOptimizer {
     map: ValidatedMap,
     army: Army,
+    config: GameConfig,
     rng: Pcg64Mcg,
     plans: HashMap<AttackPlan, AttackPlanExecutionStats>,
     plans_per_step: usize,
 }
 
 impl RandomAttackOptimizer {
-    pub fn new(map: ValidatedMap, army: Army, plans_per_step: usize) -> Self {
+    pub fn new(
+        map: ValidatedMap,
+        army: Army,
+        config: GameConfig,
+        initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+        plans_per_step: usize,
+    ) -> Self {
         Self {
             map,
             army,
+            config,
             rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
-            plans: HashMap::new(),
+            plans: initial_plan.into_iter().collect(),
             plans_per_step,
         }
     }
 
-    pub fn step(&mut self) {
+    pub fn plans(&self) -> &HashMap<AttackPlan, AttackPlanExecutionStats> {
+        &self.plans
+    }
+}
+
+impl AttackOptimizer for RandomAttackOptimizer {
+    fn step(&mut self) {
         let mut repeats = 0;
+        let mut new_plans = Vec::new();
 
         for _ in 0..self.plans_per_step {
-            let new_plan = AttackPlan::new_randomized(&self.army, &self.map, &mut self.rng);
+            let new_plan =
+                AttackPlan::new_randomized(&self.army, &self.map, &self.config, &mut self.rng);
 
-            if self.plans.contains_key(&new_plan) {
+            if self.plans.contains_key(&new_plan) || new_plans.contains(&new_plan) {
                 repeats += 1;
 
                 continue;
             }
 
-            let new_stats = execute_attack_plan(
-                &self.map,
-                &new_plan.executor_actions(&self.army),
-                ATTACK_PLAN_EXECUTIONS_COUNT,
-                ATTACK_PLAN_EXECUTOR_TPS,
-            );
-
-            self.plans.insert(new_plan, new_stats);
+            new_plans.push(new_plan);
         }
 
+        let new_plans_actions = new_plans
+            .iter()
+            .map(|new_plan| new_plan.executor_actions(&self.army, &self.map))
+            .collect::<Vec<_>>();
+        let incumbent = self
+            .plans
+            .values()
+            // plans dropped by racing have less samples
+            .filter(|stats| stats.samples_count == ATTACK_PLAN_EXECUTIONS_COUNT)
+            .max_by(|a, b| a.score.total_cmp(&b.score));
+        let new_plans_stats = race_attack_plans(
+            &self.map,
+            &self.config,
+            &new_plans_actions,
+            incumbent,
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        );
+
+        self.plans
+            .extend(new_plans.into_iter().zip(new_plans_stats));
+
         println!("{repeats}");
     }
 
-    pub fn plans(&self) -> &HashMap<AttackPlan, AttackPlanExecutionStats> {
-        &self.plans
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.plans
+            .iter()
+            .filter(|(_, stats)| stats.samples_count == ATTACK_PLAN_EXECUTIONS_COUNT)
+            .max_by(|a, b| a.1.score.total_cmp(&b.1.score))
+            .map(|(plan, stats)| (plan.executor_actions(&self.army, &self.map), stats.clone()))
     }
 }
@@ -1,10 +1,13 @@
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
         AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
         execute_attack_plan,
         v2::AttackPlan,
     },
@@ -18,8 +21,10 @@ use crate::{
 pub struct SimulatedAnnealingAttackOptimizer {
     map: ValidatedMap,
     army: Army,
+    config: GameConfig,
     rng: Pcg64Mcg,
     plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+    iterations_per_step: usize,
     current_iteration: usize,
     last_new_found_iteration: usize,
 }
@@ -28,35 +33,73 @@ impl SimulatedAnnealingAttackOptimizer {
     pub fn new(
         map: ValidatedMap,
         army: Army,
+        config: GameConfig,
         initial_plan: Option<(AttackPlan, AttackPlanExecutionStats)>,
+        iterations_per_step: usize,
     ) -> Self {
         Self {
             map,
             army,
+            config,
             rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
             plan: initial_plan,
+            iterations_per_step,
             current_iteration: 0,
             last_new_found_iteration: 0,
         }
     }
 
-    pub fn step(&mut self, iterations: usize) -> &(AttackPlan, AttackPlanExecutionStats) {
+    pub fn best_plan(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
+        self.plan.as_ref()
+    }
+
+    pub fn iterations_since_last_new_found(&self) -> usize {
+        self.current_iteration - self.last_new_found_iteration
+    }
+
+    fn radius(current_iteration: usize, last_new_found_iteration: usize) -> usize {
+        let iterations_delta = current_iteration - last_new_found_iteration;
+
+        return iterations_delta / 100 + 1;
+    }
+
+    fn init_plan(&mut self) {
+        if self.plan.is_none() {
+            let plan =
+                AttackPlan::new_randomized(&self.army, &self.map, &self.config, &mut self.rng);
+            let stats = execute_attack_plan(
+                &self.map,
+                &self.config,
+                &plan.executor_actions(&self.army, &self.map),
+                ATTACK_PLAN_EXECUTIONS_COUNT,
+                ATTACK_PLAN_EXECUTOR_TPS,
+            );
+
+            self.plan = Some((plan, stats));
+        }
+    }
+}
+
+impl AttackOptimizer for SimulatedAnnealingAttackOptimizer {
+    fn step(&mut self) {
         if self.plan.is_none() {
             self.init_plan();
         }
 
         let (plan, stats) = self.plan.as_mut().unwrap();
 
-        for _ in 0..iterations {
+        for _ in 0..self.iterations_per_step {
             let new_plan = plan.random_neighbor(
                 &self.map,
+                &self.config,
                 Self::radius(self.current_iteration, self.last_new_found_iteration),
                 &mut self.rng,
             );
 
             let new_stats = execute_attack_plan(
                 &self.map,
-                &new_plan.executor_actions(&self.army),
+                &self.config,
+                &new_plan.executor_actions(&self.army, &self.map),
                 ATTACK_PLAN_EXECUTIONS_COUNT,
                 ATTACK_PLAN_EXECUTOR_TPS,
             );
@@ -70,35 +113,10 @@ impl SimulatedAnnealingAttackOptimizer {
 
             self.current_iteration += 1;
         }
-
-        self.plan.as_ref().unwrap()
     }
 
-    pub fn best(&self) -> Option<&(AttackPlan, AttackPlanExecutionStats)> {
-        self.plan.as_ref()
-    }
-
-    pub fn iterations_since_last_new_found(&self) -> usize {
-        self.current_iteration - self.last_new_found_iteration
-    }
-
-    fn radius(current_iteration: usize, last_new_found_iteration: usize) -> usize {
-        let iterations_delta = current_iteration - last_new_found_iteration;
-
-        return iterations_delta / 100 + 1;
-    }
-
-    fn init_plan(&mut self) {
-        if self.plan.is_none() {
-            let plan = AttackPlan::new_randomized(&self.army, &self.map, &mut self.rng);
-            let stats = execute_attack_plan(
-                &self.map,
-                &plan.executor_actions(&self.army),
-                ATTACK_PLAN_EXECUTIONS_COUNT,
-                ATTACK_PLAN_EXECUTOR_TPS,
-            );
-
-            self.plan = Some((plan, stats));
-        }
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best_plan()
+            .map(|(plan, stats)| (plan.executor_actions(&self.army, &self.map), stats.clone()))
     }
 }
@@ -1,3 +1,8 @@
+use std::{
+    f32::consts::TAU,
+    iter,
+};
+
 use gomez::{
     Domain,
     Function,
@@ -9,12 +14,15 @@ use gomez::{
         Vector,
     },
 };
+use nalgebra::Vector2;
 
 use crate::{
+    GameConfig,
     ValidatedMap,
     WithCount,
     attack_optimizer::{
         Army,
+        InitialAttackPlan,
         attack_plan_executor::{
             AttackPlanExecutorAction,
             Spawnable,
@@ -28,6 +36,7 @@ use crate::{
     consts::{
         ATTACK_PLAN_EXECUTIONS_COUNT,
         ATTACK_PLAN_EXECUTOR_TPS,
+        MAX_UNIT_GROUP_SPREAD,
     },
 };
 
@@ -35,6 +44,7 @@ use crate::{
 pub struct AttackPlan {
     pub map: ValidatedMap,
     pub army: Army,
+    pub config: GameConfig,
 }
 
 impl AttackPlan {
@@ -43,20 +53,23 @@ impl AttackPlan {
         mut x: impl Iterator<Item = f32>,
     ) -> Vec<AttackPlanExecutorAction> {
         let mut result = Vec::new();
+        let center = Vector2::from_element(self.map.size().total_size() as f32 / 2.0);
 
         for unit_with_count in &self.army.units {
             let unit_group = AttackPlanUnitGroup::from_numbers(&mut x);
 
             let position = unit_group.cartesian_position(&self.map.size(), self.map.drop_zone());
 
-            result.push(AttackPlanExecutorAction {
-                spawnable: Spawnable::UnitGroup(WithCount {
+            result.push(unit_group.formation.executor_action(
+                WithCount {
                     value: unit_with_count.value.clone(),
                     count: unit_with_count.count,
-                }),
+                },
                 position,
-                drop_time: unit_group.drop_time,
-            });
+                center,
+                unit_group.spread,
+                unit_group.drop_time,
+            ));
         }
 
         for spell_with_count in &self.army.spells {
@@ -76,29 +89,105 @@ impl AttackPlan {
 
         result
     }
-}
 
-impl Problem for AttackPlan {
-    type Field = f32;
+    /// `(lower, upper)` bound of each component of `x` used by
+    /// [`Self::executor_actions`]. Bounds may be infinite.
+    pub fn bounds(&self) -> impl Iterator<Item = (f32, f32)> {
+        let unit_bounds = self
+            .army
+            .units
+            .iter()
+            .flat_map(|_| AttackPlanUnitGroup::domain(&self.config));
+        let spell_bounds = self
+            .army
+            .spells
+            .iter()
+            .flat_map(|_| AttackPlanSpellGroup::domain(&self.config));
+
+        unit_bounds.chain(spell_bounds)
+    }
 
-    fn domain(&self) -> Domain<Self::Field> {
-        let mut lower = Vec::new();
-        let mut upper = Vec::new();
+    /// `x` for [`Self::executor_actions`] with groups present in
+    /// `initial_plan` placed as in it. Other groups are in the middle of
+    /// domain.
+    pub fn initial_x(&self, initial_plan: &InitialAttackPlan) -> anyhow::Result<Vec<f32>> {
+        let placements = initial_plan.placements(&self.army)?;
+        let (unit_placements, spell_placements) = placements.split_at(self.army.units.len());
+        let mut middle = self.denormalize(iter::repeat(0.5)).into_iter();
+        let mut result = Vec::new();
 
-        for _ in &self.army.units {
-            for (lower_bound, upper_bound) in AttackPlanUnitGroup::domain() {
-                lower.push(lower_bound);
-                upper.push(upper_bound);
+        for placement in unit_placements {
+            let mut unit_group = AttackPlanUnitGroup::from_numbers(&mut middle);
+
+            if let Some(placement) = placement {
+                unit_group.set_cartesian_position(
+                    placement.position,
+                    &self.map.size(),
+                    self.map.drop_zone(),
+                );
+                unit_group.drop_time = placement
+                    .drop_time
+                    .clamp(0.0, self.config.max_unit_drop_time);
+                unit_group.formation = placement.formation;
+                unit_group.spread = placement.spread.clamp(0.0, MAX_UNIT_GROUP_SPREAD);
             }
+
+            result.extend(unit_group.to_numbers());
         }
 
-        for _ in &self.army.spells {
-            for (lower_bound, upper_bound) in AttackPlanSpellGroup::domain() {
-                lower.push(lower_bound);
-                upper.push(upper_bound);
+        for placement in spell_placements {
+            let mut spell_group = AttackPlanSpellGroup::from_numbers(&mut middle);
+
+            if let Some(placement) = placement {
+                spell_group.position = (placement.position / self.map.size().total_size() as f32)
+                    .map(|x| x.clamp(0.0, 1.0));
+                spell_group.drop_time = placement
+                    .drop_time
+                    .clamp(0.0, self.config.max_unit_drop_time);
             }
+
+            result.extend(spell_group.to_numbers());
         }
 
+        Ok(result)
+    }
+
+    /// Maps point of unit cube to `x` for [`Self::executor_actions`].
+    /// Components with finite bounds are scaled to bounds. Infinite bounds
+    /// are used only for angles, so such components are measured in full
+    /// turns.
+    pub fn denormalize(&self, y: impl Iterator<Item = f32>) -> Vec<f32> {
+        y.zip(self.bounds())
+            .map(|(y, (lower, upper))| {
+                if lower.is_finite() && upper.is_finite() {
+                    lower + y * (upper - lower)
+                } else {
+                    y * TAU
+                }
+            })
+            .collect()
+    }
+
+    /// Inverse of [`Self::denormalize`].
+    pub fn normalize(&self, x: impl Iterator<Item = f32>) -> Vec<f32> {
+        x.zip(self.bounds())
+            .map(|(x, (lower, upper))| {
+                if lower.is_finite() && upper.is_finite() {
+                    (x - lower) / (upper - lower)
+                } else {
+                    x / TAU
+                }
+            })
+            .collect()
+    }
+}
+
+impl Problem for AttackPlan {
+    type Field = f32;
+
+    fn domain(&self) -> Domain<Self::Field> {
+        let (lower, upper): (Vec<_>, Vec<_>) = self.bounds().unzip();
+
         Domain::rect(lower, upper)
     }
 }
@@ -110,6 +199,7 @@ impl Function for AttackPlan {
     {
         -execute_attack_plan(
             &self.map,
+            &self.config,
             &self.executor_actions(x.iter().cloned()),
             ATTACK_PLAN_EXECUTIONS_COUNT,
             ATTACK_PLAN_EXECUTOR_TPS,
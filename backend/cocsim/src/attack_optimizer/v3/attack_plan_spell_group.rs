@@ -1,7 +1,7 @@
 use nalgebra::Vector2;
 
 use crate::{
-    consts::MAX_UNIT_DROP_TIME,
+    GameConfig,
     game::features::map_size::MapSize,
 };
 
@@ -20,11 +20,16 @@ impl AttackPlanSpellGroup {
         }
     }
 
-    pub fn domain() -> impl Iterator<Item = (f32, f32)> {
+    /// Inverse of [`Self::from_numbers`].
+    pub fn to_numbers(&self) -> [f32; 3] {
+        [self.position.x, self.position.y, self.drop_time]
+    }
+
+    pub fn domain(config: &GameConfig) -> impl Iterator<Item = (f32, f32)> {
         [
-            (0.0, 1.0),                // position.x
-            (0.0, 1.0),                // position.y
-            (0.0, MAX_UNIT_DROP_TIME), // drop_time
+            (0.0, 1.0),                       // position.x
+            (0.0, 1.0),                       // position.y
+            (0.0, config.max_unit_drop_time), // drop_time
         ]
         .into_iter()
     }
@@ -11,7 +11,9 @@ use nalgebra::{
 };
 
 use crate::{
-    consts::MAX_UNIT_DROP_TIME,
+    GameConfig,
+    attack_optimizer::UnitGroupFormation,
+    consts::MAX_UNIT_GROUP_SPREAD,
     game::features::map_size::MapSize,
     geometry::{
         Ray,
@@ -27,6 +29,9 @@ pub struct AttackPlanUnitGroup {
     /// from 0 to 1
     pub distance: f32,
     pub drop_time: f32,
+    pub formation: UnitGroupFormation,
+    /// Formation length in tiles.
+    pub spread: f32,
 }
 
 impl AttackPlanUnitGroup {
@@ -35,14 +40,29 @@ impl AttackPlanUnitGroup {
             angle: x.next().unwrap(),
             distance: x.next().unwrap(),
             drop_time: x.next().unwrap(),
+            formation: UnitGroupFormation::from_number(x.next().unwrap()),
+            spread: x.next().unwrap(),
         }
     }
 
-    pub fn domain() -> impl Iterator<Item = (f32, f32)> {
+    /// Inverse of [`Self::from_numbers`].
+    pub fn to_numbers(&self) -> [f32; 5] {
         [
-            (NEG_INFINITY, INFINITY),  // angle
-            (0.0, 1.0),                // distance
-            (0.0, MAX_UNIT_DROP_TIME), // drop_time
+            self.angle,
+            self.distance,
+            self.drop_time,
+            self.formation.to_number(),
+            self.spread,
+        ]
+    }
+
+    pub fn domain(config: &GameConfig) -> impl Iterator<Item = (f32, f32)> {
+        [
+            (NEG_INFINITY, INFINITY),                    // angle
+            (0.0, 1.0),                                  // distance
+            (0.0, config.max_unit_drop_time),            // drop_time
+            (0.0, UnitGroupFormation::ALL.len() as f32), // formation
+            (0.0, MAX_UNIT_GROUP_SPREAD),                // spread
         ]
         .into_iter()
     }
@@ -135,4 +155,40 @@ impl AttackPlanUnitGroup {
 
         start_point + (stop_point - start_point) * clamp(self.distance, 0.01, 0.99) // clamp for unit to not spawn on right or bottom border
     }
+
+    /// Sets `angle` and `distance`, so [`Self::cartesian_position`] is as
+    /// close to `position` as possible.
+    pub fn set_cartesian_position(
+        &mut self,
+        position: Vector2<f32>,
+        map_size: &MapSize,
+        drop_zone: &DMatrix<bool>,
+    ) {
+        let center = Vector2::from_element(map_size.total_size() as f32 / 2.0);
+        let offset = position - center;
+
+        if offset.norm() > 0.0 {
+            self.angle = offset.y.atan2(offset.x);
+        }
+
+        self.distance = 0.0;
+
+        let first = self.cartesian_position(map_size, drop_zone);
+
+        self.distance = 1.0;
+
+        let segment = self.cartesian_position(map_size, drop_zone) - first;
+        let t = if segment.norm_squared() > 0.0 {
+            clamp(
+                (position - first).dot(&segment) / segment.norm_squared(),
+                0.0,
+                1.0,
+            )
+        } else {
+            0.0
+        };
+
+        // inverse of distance clamping in cartesian_position
+        self.distance = 0.01 + 0.98 * t;
+    }
 }
@@ -0,0 +1,299 @@
+use std::f32::consts::{
+    PI,
+    SQRT_2,
+};
+
+use nalgebra::{
+    Cholesky,
+    DMatrix,
+    DVector,
+    Dyn,
+};
+use rand::Rng;
+use rand_pcg::Pcg64Mcg;
+
+use crate::{
+    attack_optimizer::{
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan,
+        v3::AttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        RNG_INITIAL_STATE,
+    },
+};
+
+/// Random plans evaluated before surrogate is used.
+const INITIAL_SAMPLES: usize = 10;
+/// Candidates uniformly distributed in the whole domain.
+const GLOBAL_CANDIDATES: usize = 500;
+/// Candidates near the best plan.
+const LOCAL_CANDIDATES: usize = 500;
+/// In normalized coordinates.
+const LOCAL_CANDIDATES_RADIUS: f32 = 0.1;
+/// Length scales tried when fitting surrogate, multiplied by square root of
+/// dimension.
+const LENGTH_SCALES: [f32; 5] = [0.05, 0.1, 0.2, 0.4, 0.8];
+/// Relative to normalized scores variance. Scores are deterministic, this is
+/// for numerical stability.
+const NOISE_VARIANCE: f32 = 1e-4;
+/// Surrogate is fitted to the best half of this many plans and the latest
+/// other plans, so choosing plan doesn't get slower after that.
+const MAX_SURROGATE_OBSERVATIONS: usize = 200;
+
+/// [`AttackOptimizer`] for cases where plan evaluation is expensive (e.g.
+/// single threaded wasm). Fits Gaussian process to evaluated plans and
+/// evaluates plan with the largest expected improvement, so each simulated
+/// plan is chosen carefully. Choosing costs `O(n^3)` for `n` plans surrogate is
+/// fitted to, so at most [`MAX_SURROGATE_OBSERVATIONS`] plans are used.
+pub struct BayesianAttackOptimizer {
+    plan: AttackPlan,
+    dim: usize,
+    rng: Pcg64Mcg,
+    /// Normalized `x` (see [`AttackPlan::denormalize`]) and score.
+    observations: Vec<(DVector<f32>, f32)>,
+    best: Option<(Vec<f32>, AttackPlanExecutionStats)>,
+    evaluations_per_step: usize,
+}
+
+impl BayesianAttackOptimizer {
+    /// `initial_x` is evaluated first if it's provided.
+    pub fn new(plan: AttackPlan, initial_x: Option<Vec<f32>>, evaluations_per_step: usize) -> Self {
+        let dim = plan.bounds().count();
+        let mut result = Self {
+            plan,
+            dim,
+            rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
+            observations: Vec::new(),
+            best: None,
+            evaluations_per_step,
+        };
+
+        if let Some(initial_x) = initial_x {
+            let initial_y = DVector::from_vec(result.plan.normalize(initial_x.into_iter()));
+
+            result.evaluate(initial_y);
+        }
+
+        result
+    }
+
+    /// Best point of [`AttackPlan`] domain found so far.
+    pub fn best_x(&self) -> Option<&(Vec<f32>, AttackPlanExecutionStats)> {
+        self.best.as_ref()
+    }
+
+    /// Number of simulated plans.
+    pub fn evaluations_count(&self) -> usize {
+        self.observations.len()
+    }
+
+    fn random_point(&mut self) -> DVector<f32> {
+        DVector::from_fn(self.dim, |_, _| self.rng.random::<f32>())
+    }
+
+    fn next_point(&mut self) -> DVector<f32> {
+        if self.observations.len() < INITIAL_SAMPLES {
+            return self.random_point();
+        }
+
+        // covariance matrix isn't positive definite in f32 for (nearly)
+        // duplicate points with any length scale
+        let Some(surrogate) = GaussianProcess::fit(&self.surrogate_observations()) else {
+            return self.random_point();
+        };
+        let best_observation = self
+            .observations
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap()
+            .0
+            .clone();
+        let mut candidates = Vec::with_capacity(GLOBAL_CANDIDATES + LOCAL_CANDIDATES);
+
+        for _ in 0..GLOBAL_CANDIDATES {
+            candidates.push(self.random_point());
+        }
+
+        for _ in 0..LOCAL_CANDIDATES {
+            let offset = DVector::from_fn(self.dim, |_, _| {
+                self.rng
+                    .random_range(-LOCAL_CANDIDATES_RADIUS..=LOCAL_CANDIDATES_RADIUS)
+            });
+
+            candidates.push((&best_observation + offset).map(|y| y.clamp(0.0, 1.0)));
+        }
+
+        let expected_improvement = surrogate.expected_improvement(&candidates);
+
+        candidates.swap_remove(expected_improvement.argmax().0)
+    }
+
+    fn evaluate(&mut self, y: DVector<f32>) {
+        let x = self.plan.denormalize(y.iter().cloned());
+        let stats = execute_attack_plan(
+            &self.plan.map,
+            &self.plan.config,
+            &self.plan.executor_actions(x.iter().cloned()),
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        );
+
+        self.observations.push((y, stats.score));
+
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|(_, best_stats)| stats.score > best_stats.score)
+        {
+            self.best = Some((x, stats));
+        }
+    }
+
+    /// The best half of [`MAX_SURROGATE_OBSERVATIONS`] and the latest other
+    /// observations.
+    fn surrogate_observations(&self) -> Vec<(DVector<f32>, f32)> {
+        if self.observations.len() <= MAX_SURROGATE_OBSERVATIONS {
+            return self.observations.clone();
+        }
+
+        let mut indices = (0..self.observations.len()).collect::<Vec<_>>();
+
+        indices
+            .sort_unstable_by(|a, b| self.observations[*b].1.total_cmp(&self.observations[*a].1));
+
+        let (best, others) = indices.split_at_mut(MAX_SURROGATE_OBSERVATIONS / 2);
+
+        others.sort_unstable_by(|a, b| b.cmp(a));
+
+        best.iter()
+            .chain(others.iter())
+            .take(MAX_SURROGATE_OBSERVATIONS)
+            .map(|i| self.observations[*i].clone())
+            .collect()
+    }
+}
+
+impl AttackOptimizer for BayesianAttackOptimizer {
+    fn step(&mut self) {
+        for _ in 0..self.evaluations_per_step {
+            let y = self.next_point();
+
+            self.evaluate(y);
+        }
+    }
+
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best
+            .as_ref()
+            .map(|(x, stats)| (self.plan.executor_actions(x.iter().cloned()), stats.clone()))
+    }
+}
+
+/// Gaussian process with squared exponential kernel fitted to normalized
+/// scores. Length scale is chosen by marginal likelihood, [`None`] if fit fails
+/// with every length scale.
+struct GaussianProcess {
+    points: Vec<DVector<f32>>,
+    length_scale: f32,
+    cholesky: Cholesky<f32, Dyn>,
+    /// `K^-1 * scores`.
+    alpha: DVector<f32>,
+    /// Best normalized score.
+    best_score: f32,
+}
+
+impl GaussianProcess {
+    fn fit(observations: &[(DVector<f32>, f32)]) -> Option<Self> {
+        let points = observations
+            .iter()
+            .map(|(y, _)| y.clone())
+            .collect::<Vec<_>>();
+        let scores = DVector::from_iterator(
+            observations.len(),
+            observations.iter().map(|(_, score)| *score),
+        );
+        let score_mean = scores.mean();
+        let score_deviation = scores.variance().sqrt().max(f32::EPSILON);
+        let scores = scores.map(|score| (score - score_mean) / score_deviation);
+        let dim_scale = (points[0].len().max(1) as f32).sqrt();
+
+        LENGTH_SCALES
+            .iter()
+            .filter_map(|length_scale| {
+                let length_scale = length_scale * dim_scale;
+                let covariance = DMatrix::from_fn(points.len(), points.len(), |i, j| {
+                    kernel(&points[i], &points[j], length_scale)
+                        + if i == j { NOISE_VARIANCE } else { 0.0 }
+                });
+                let cholesky = covariance.cholesky()?;
+                let alpha = cholesky.solve(&scores);
+                let log_likelihood =
+                    -0.5 * scores.dot(&alpha) - cholesky.l().diagonal().map(f32::ln).sum();
+
+                Some((
+                    log_likelihood,
+                    Self {
+                        points: points.clone(),
+                        length_scale,
+                        cholesky,
+                        alpha,
+                        best_score: scores.max(),
+                    },
+                ))
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, surrogate)| surrogate)
+    }
+
+    fn expected_improvement(&self, candidates: &[DVector<f32>]) -> DVector<f32> {
+        let cross_covariance = DMatrix::from_fn(self.points.len(), candidates.len(), |i, j| {
+            kernel(&self.points[i], &candidates[j], self.length_scale)
+        });
+        let mean = cross_covariance.tr_mul(&self.alpha);
+        let v = self
+            .cholesky
+            .l()
+            .solve_lower_triangular(&cross_covariance)
+            .unwrap();
+
+        DVector::from_fn(candidates.len(), |j, _| {
+            let deviation = (1.0 - v.column(j).norm_squared()).max(0.0).sqrt();
+
+            if deviation == 0.0 {
+                return 0.0;
+            }
+
+            let improvement = mean[j] - self.best_score;
+            let z = improvement / deviation;
+
+            improvement * normal_cdf(z) + deviation * normal_pdf(z)
+        })
+    }
+}
+
+fn kernel(a: &DVector<f32>, b: &DVector<f32>, length_scale: f32) -> f32 {
+    (-a.metric_distance(b).powi(2) / (2.0 * length_scale * length_scale)).exp()
+}
+
+fn normal_pdf(x: f32) -> f32 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f32) -> f32 {
+    0.5 * (1.0 + erf(x / SQRT_2))
+}
+
+/// Abramowitz and Stegun approximation 7.1.26, max error is 1.5e-7.
+fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial =
+        t * (0.2548296 + t * (-0.28449672 + t * (1.4214138 + t * (-1.4531521 + t * 1.0614054))));
+    let result = 1.0 - polynomial * (-x * x).exp();
+
+    result.copysign(x)
+}
@@ -0,0 +1,353 @@
+use std::f32::consts::TAU;
+
+use nalgebra::{
+    DMatrix,
+    DVector,
+    SymmetricEigen,
+};
+use rand::Rng;
+use rand_pcg::Pcg64Mcg;
+
+use crate::{
+    attack_optimizer::{
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan,
+        race_attack_plans,
+        v3::AttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        RNG_INITIAL_STATE,
+    },
+};
+
+/// In normalized coordinates (see [`AttackPlan::denormalize`]).
+const INITIAL_SIGMA: f32 = 0.3;
+/// Used when starting from user-provided plan.
+const WARM_START_SIGMA: f32 = 0.1;
+/// IPOP multiplies population size by this value on each restart.
+const POPULATION_GROWTH: usize = 2;
+const MIN_STEP_SIZE: f32 = 1e-4;
+const MAX_AXIS_RATIO: f32 = 1e7;
+const MIN_EIGENVALUE: f32 = 1e-20;
+
+/// [`AttackOptimizer`] running CMA-ES on [`AttackPlan`] with IPOP restarts:
+/// when search converges or stagnates, it starts again from random point
+/// with doubled population. One [`AttackOptimizer::step`] is one generation.
+/// Generation is evaluated with [`race_attack_plans`], so executions run in
+/// parallel if `rayon` feature is enabled.
+pub struct CmaEsAttackOptimizer {
+    plan: AttackPlan,
+    bounds: Vec<(f32, f32)>,
+    rng: Pcg64Mcg,
+    state: CmaEsState,
+    restarts: usize,
+    best: Option<(Vec<f32>, AttackPlanExecutionStats)>,
+}
+
+impl CmaEsAttackOptimizer {
+    /// First run is centered at `initial_x` with smaller step size if it's
+    /// provided (it's also evaluated), otherwise at the middle of domain.
+    pub fn new(plan: AttackPlan, initial_x: Option<Vec<f32>>) -> Self {
+        let bounds = plan.bounds().collect::<Vec<_>>();
+        let dim = bounds.len();
+        let population_size = 4 + (3.0 * (dim.max(1) as f32).ln()) as usize;
+        let (mean, sigma) = match &initial_x {
+            Some(initial_x) => (
+                DVector::from_vec(plan.normalize(initial_x.iter().cloned())),
+                WARM_START_SIGMA,
+            ),
+            None => (DVector::from_element(dim, 0.5), INITIAL_SIGMA),
+        };
+        let best = initial_x.map(|initial_x| {
+            let stats = execute_attack_plan(
+                &plan.map,
+                &plan.config,
+                &plan.executor_actions(initial_x.iter().cloned()),
+                ATTACK_PLAN_EXECUTIONS_COUNT,
+                ATTACK_PLAN_EXECUTOR_TPS,
+            );
+
+            (initial_x, stats)
+        });
+
+        Self {
+            plan,
+            bounds,
+            rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
+            state: CmaEsState::new(mean, sigma, population_size),
+            restarts: 0,
+            best,
+        }
+    }
+
+    /// Best point of [`AttackPlan`] domain found so far.
+    pub fn best_x(&self) -> Option<&(Vec<f32>, AttackPlanExecutionStats)> {
+        self.best.as_ref()
+    }
+
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    pub fn population_size(&self) -> usize {
+        self.state.population_size
+    }
+
+    /// Moves sample inside of bounds. Repaired sample is used for update too,
+    /// so mean doesn't drift away from the domain.
+    fn repair(&self, y: &mut DVector<f32>) {
+        for (y, (lower, upper)) in y.iter_mut().zip(&self.bounds) {
+            if lower.is_finite() && upper.is_finite() {
+                *y = y.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    fn evaluate(&self, y: &DVector<f32>) -> AttackPlanExecutionStats {
+        execute_attack_plan(
+            &self.plan.map,
+            &self.plan.config,
+            &self
+                .plan
+                .executor_actions(self.plan.denormalize(y.iter().cloned()).into_iter()),
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        )
+    }
+
+    fn restart(&mut self) {
+        let dim = self.bounds.len();
+        let mean = DVector::from_fn(dim, |_, _| self.rng.random::<f32>());
+
+        self.restarts += 1;
+        self.state = CmaEsState::new(
+            mean,
+            INITIAL_SIGMA,
+            self.state.population_size * POPULATION_GROWTH,
+        );
+    }
+}
+
+impl AttackOptimizer for CmaEsAttackOptimizer {
+    fn step(&mut self) {
+        if self.bounds.is_empty() {
+            if self.best.is_none() {
+                self.best = Some((Vec::new(), self.evaluate(&DVector::zeros(0))));
+            }
+
+            return;
+        }
+
+        let mut population = self.state.sample(&mut self.rng);
+
+        for y in &mut population {
+            self.repair(y);
+        }
+
+        let actions = population
+            .iter()
+            .map(|y| {
+                self.plan
+                    .executor_actions(self.plan.denormalize(y.iter().cloned()).into_iter())
+            })
+            .collect::<Vec<_>>();
+        let stats = race_attack_plans(
+            &self.plan.map,
+            &self.plan.config,
+            &actions,
+            self.best.as_ref().map(|(_, stats)| stats),
+            ATTACK_PLAN_EXECUTIONS_COUNT,
+            ATTACK_PLAN_EXECUTOR_TPS,
+        );
+
+        let mut ranked = population.into_iter().zip(stats).collect::<Vec<_>>();
+
+        ranked.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+
+        // plans dropped by racing have less samples
+        if let Some((best_y, best_stats)) = ranked
+            .iter()
+            .find(|(_, stats)| stats.samples_count == ATTACK_PLAN_EXECUTIONS_COUNT)
+            && self
+                .best
+                .as_ref()
+                .is_none_or(|(_, stats)| best_stats.score > stats.score)
+        {
+            self.best = Some((
+                self.plan.denormalize(best_y.iter().cloned()),
+                best_stats.clone(),
+            ));
+        }
+
+        self.state.update(&ranked);
+
+        if self.state.should_restart() {
+            self.restart();
+        }
+    }
+
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best
+            .as_ref()
+            .map(|(x, stats)| (self.plan.executor_actions(x.iter().cloned()), stats.clone()))
+    }
+}
+
+/// Single CMA-ES run with default strategy parameters (see "The CMA Evolution
+/// Strategy: A Tutorial" by N. Hansen). Score is maximized.
+struct CmaEsState {
+    population_size: usize,
+    weights: DVector<f32>,
+    mu_eff: f32,
+    cc: f32,
+    cs: f32,
+    c1: f32,
+    cmu: f32,
+    damps: f32,
+    chi_n: f32,
+    mean: DVector<f32>,
+    sigma: f32,
+    pc: DVector<f32>,
+    ps: DVector<f32>,
+    c: DMatrix<f32>,
+    /// Eigenvectors of `c`.
+    b: DMatrix<f32>,
+    /// Square roots of eigenvalues of `c`.
+    d: DVector<f32>,
+    generation: usize,
+    best_score: f32,
+    stagnant_generations: usize,
+}
+
+impl CmaEsState {
+    fn new(mean: DVector<f32>, sigma: f32, population_size: usize) -> Self {
+        let n = mean.len() as f32;
+        let mu = population_size / 2;
+        let weights = DVector::from_fn(mu, |i, _| (mu as f32 + 0.5).ln() - (i as f32 + 1.0).ln());
+        let weights = &weights / weights.sum();
+        let mu_eff = 1.0 / weights.norm_squared();
+        let cc = (4.0 + mu_eff / n) / (n + 4.0 + 2.0 * mu_eff / n);
+        let cs = (mu_eff + 2.0) / (n + mu_eff + 5.0);
+        let c1 = 2.0 / ((n + 1.3).powi(2) + mu_eff);
+        let cmu =
+            (1.0 - c1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((n + 2.0).powi(2) + mu_eff));
+        let damps = 1.0 + 2.0 * (((mu_eff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+        let dim = mean.len();
+
+        Self {
+            population_size,
+            weights,
+            mu_eff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chi_n,
+            mean,
+            sigma,
+            pc: DVector::zeros(dim),
+            ps: DVector::zeros(dim),
+            c: DMatrix::identity(dim, dim),
+            b: DMatrix::identity(dim, dim),
+            d: DVector::from_element(dim, 1.0),
+            generation: 0,
+            best_score: f32::NEG_INFINITY,
+            stagnant_generations: 0,
+        }
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> Vec<DVector<f32>> {
+        (0..self.population_size)
+            .map(|_| {
+                let z = DVector::from_fn(self.mean.len(), |_, _| standard_normal(rng));
+
+                &self.mean + self.sigma * (&self.b * z.component_mul(&self.d))
+            })
+            .collect()
+    }
+
+    /// `ranked` is population sorted by score, best first.
+    fn update(&mut self, ranked: &[(DVector<f32>, AttackPlanExecutionStats)]) {
+        let n = self.mean.len() as f32;
+        let old_mean = self.mean.clone();
+
+        self.generation += 1;
+        self.mean = ranked
+            .iter()
+            .zip(self.weights.iter())
+            .fold(DVector::zeros(self.mean.len()), |mean, ((y, _), w)| {
+                mean + y * *w
+            });
+
+        let mean_step = (&self.mean - &old_mean) / self.sigma;
+        let inv_sqrt_c =
+            &self.b * DMatrix::from_diagonal(&self.d.map(|d| 1.0 / d)) * self.b.transpose();
+
+        self.ps = (1.0 - self.cs) * &self.ps
+            + (self.cs * (2.0 - self.cs) * self.mu_eff).sqrt() * (inv_sqrt_c * &mean_step);
+
+        let hsig = self.ps.norm()
+            / (1.0 - (1.0 - self.cs).powi(2 * self.generation as i32)).sqrt()
+            / self.chi_n
+            < 1.4 + 2.0 / (n + 1.0);
+        let hsig = if hsig { 1.0 } else { 0.0 };
+
+        self.pc = (1.0 - self.cc) * &self.pc
+            + hsig * (self.cc * (2.0 - self.cc) * self.mu_eff).sqrt() * &mean_step;
+
+        let rank_mu = ranked.iter().zip(self.weights.iter()).fold(
+            DMatrix::zeros(self.mean.len(), self.mean.len()),
+            |rank_mu, ((y, _), w)| {
+                let step = (y - &old_mean) / self.sigma;
+
+                rank_mu + *w * &step * step.transpose()
+            },
+        );
+
+        self.c = (1.0 - self.c1 - self.cmu) * &self.c
+            + self.c1
+                * (&self.pc * self.pc.transpose()
+                    + (1.0 - hsig) * self.cc * (2.0 - self.cc) * &self.c)
+            + self.cmu * rank_mu;
+        self.c = (&self.c + self.c.transpose()) / 2.0;
+        self.sigma *= ((self.cs / self.damps) * (self.ps.norm() / self.chi_n - 1.0)).exp();
+
+        let eigen = SymmetricEigen::new(self.c.clone());
+
+        self.b = eigen.eigenvectors;
+        self.d = eigen.eigenvalues.map(|x| x.max(MIN_EIGENVALUE).sqrt());
+
+        let generation_best_score = ranked[0].1.score;
+
+        if generation_best_score > self.best_score {
+            self.best_score = generation_best_score;
+            self.stagnant_generations = 0;
+        } else {
+            self.stagnant_generations += 1;
+        }
+    }
+
+    fn should_restart(&self) -> bool {
+        let max_stagnant_generations =
+            10 + (30.0 * self.mean.len() as f32 / self.population_size as f32).ceil() as usize;
+
+        self.sigma * self.d.max() < MIN_STEP_SIZE
+            || self.d.max() > MAX_AXIS_RATIO * self.d.min()
+            || self.stagnant_generations > max_stagnant_generations
+            || !self.sigma.is_finite()
+    }
+}
+
+/// Box-Muller transform.
+fn standard_normal(rng: &mut impl Rng) -> f32 {
+    let u1 = 1.0 - rng.random::<f32>();
+    let u2 = rng.random::<f32>();
+
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
@@ -0,0 +1,106 @@
+use gomez::{
+    Domain,
+    Optimizer,
+    Problem,
+    algo::Lipo,
+    nalgebra::DVector,
+};
+
+use crate::{
+    attack_optimizer::{
+        AttackOptimizer,
+        AttackPlanExecutionStats,
+        attack_plan_executor::AttackPlanExecutorAction,
+        execute_attack_plan,
+        v3::AttackPlan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+        RNG_INITIAL_STATE,
+    },
+};
+
+/// [`AttackOptimizer`] driving [`Lipo`] on [`AttackPlan`].
+pub struct LipoAttackOptimizer {
+    plan: AttackPlan,
+    domain: Domain<f32>,
+    lipo: Lipo<AttackPlan>,
+    x: DVector<f32>,
+    best: Option<(Vec<f32>, AttackPlanExecutionStats)>,
+    iterations_per_step: usize,
+}
+
+impl LipoAttackOptimizer {
+    /// `initial_x` is evaluated and added to [`Lipo`] evaluations.
+    pub fn new(plan: AttackPlan, initial_x: Option<Vec<f32>>, iterations_per_step: usize) -> Self {
+        let domain = plan.domain();
+        let mut lipo = Lipo::new(
+            &plan,
+            &domain,
+            fastrand::Rng::with_seed(RNG_INITIAL_STATE as u64),
+        );
+        let x = DVector::zeros(domain.dim());
+        let best = initial_x.map(|initial_x| {
+            let stats = execute_attack_plan(
+                &plan.map,
+                &plan.config,
+                &plan.executor_actions(initial_x.iter().cloned()),
+                ATTACK_PLAN_EXECUTIONS_COUNT,
+                ATTACK_PLAN_EXECUTOR_TPS,
+            );
+
+            lipo.add_evaluation(DVector::from_vec(initial_x.clone()), -stats.score)
+                .expect("First evaluation is always accepted");
+
+            (initial_x, stats)
+        });
+
+        Self {
+            plan,
+            domain,
+            lipo,
+            x,
+            best,
+            iterations_per_step,
+        }
+    }
+
+    /// Best point of [`AttackPlan`] domain found so far.
+    pub fn best_x(&self) -> Option<&(Vec<f32>, AttackPlanExecutionStats)> {
+        self.best.as_ref()
+    }
+}
+
+impl AttackOptimizer for LipoAttackOptimizer {
+    fn step(&mut self) {
+        for _ in 0..self.iterations_per_step {
+            let Ok(fx) = self.lipo.opt_next(&self.plan, &self.domain, &mut self.x) else {
+                break;
+            };
+
+            // function value is negated score
+            if self
+                .best
+                .as_ref()
+                .is_none_or(|(_, best_stats)| -fx > best_stats.score)
+            {
+                let stats = execute_attack_plan(
+                    &self.plan.map,
+                    &self.plan.config,
+                    &self.plan.executor_actions(self.x.iter().cloned()),
+                    ATTACK_PLAN_EXECUTIONS_COUNT,
+                    ATTACK_PLAN_EXECUTOR_TPS,
+                );
+
+                self.best = Some((self.x.iter().cloned().collect(), stats));
+            }
+        }
+    }
+
+    fn best(&self) -> Option<(Vec<AttackPlanExecutorAction>, AttackPlanExecutionStats)> {
+        self.best
+            .as_ref()
+            .map(|(x, stats)| (self.plan.executor_actions(x.iter().cloned()), stats.clone()))
+    }
+}
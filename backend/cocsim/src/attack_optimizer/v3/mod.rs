@@ -1,7 +1,13 @@
 mod attack_plan;
 mod attack_plan_spell_group;
 mod attack_plan_unit_group;
+mod bayesian_attack_optimizer;
+mod cma_es_attack_optimizer;
+mod lipo_attack_optimizer;
 
 pub use attack_plan::AttackPlan;
 pub use attack_plan_spell_group::AttackPlanSpellGroup;
 pub use attack_plan_unit_group::AttackPlanUnitGroup;
+pub use bayesian_attack_optimizer::BayesianAttackOptimizer;
+pub use cma_es_attack_optimizer::CmaEsAttackOptimizer;
+pub use lipo_attack_optimizer::LipoAttackOptimizer;
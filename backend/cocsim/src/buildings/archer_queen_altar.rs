@@ -0,0 +1,124 @@
+use arbitrary::Arbitrary;
+use hecs::World;
+use nalgebra::Vector2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    ShapeColor,
+    UsizeWithMax,
+    buildings::{
+        BuildingModel,
+        BuildingType,
+        utils::hero_altar::{
+            DefendingHero,
+            spawn_hero_altar,
+        },
+    },
+    game::features::actions::TargetProjectileAttack,
+};
+
+struct ArcherQueenAltarLevel {
+    pub hero_health: f32,
+    pub hero_attack_damage: f32,
+}
+
+const ARCHER_QUEEN_ALTAR_LEVELS_LEN: usize = 10;
+const ARCHER_QUEEN_ALTAR_LEVEL_INDEX_MAX: usize = ARCHER_QUEEN_ALTAR_LEVELS_LEN - 1;
+const ARCHER_QUEEN_ALTAR_LEVELS: [ArcherQueenAltarLevel; ARCHER_QUEEN_ALTAR_LEVELS_LEN] = [
+    ArcherQueenAltarLevel {
+        hero_health: 580.0,
+        hero_attack_damage: 102.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 592.0,
+        hero_attack_damage: 105.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 604.0,
+        hero_attack_damage: 108.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 617.0,
+        hero_attack_damage: 111.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 630.0,
+        hero_attack_damage: 114.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 643.0,
+        hero_attack_damage: 117.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 657.0,
+        hero_attack_damage: 120.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 671.0,
+        hero_attack_damage: 123.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 685.0,
+        hero_attack_damage: 126.0,
+    },
+    ArcherQueenAltarLevel {
+        hero_health: 700.0,
+        hero_attack_damage: 129.75,
+    },
+];
+
+const ARCHER_QUEEN_ALTAR: BuildingType = BuildingType {
+    name: "ArcherQueenAltar",
+    size: Vector2::new(3, 3),
+    levels: ARCHER_QUEEN_ALTAR_LEVELS.len(),
+    options: &[],
+    affects_drop_zone: true,
+};
+
+inventory::submit! {ARCHER_QUEEN_ALTAR}
+
+const ARCHER_QUEEN_ALTAR_HEALTH: f32 = 250.0;
+const ARCHER_QUEEN_ALTAR_RANGE: f32 = 9.0;
+const ARCHER_QUEEN_ALTAR_HERO_SPEED: f32 = 3.0;
+const ARCHER_QUEEN_ALTAR_HERO_ATTACK_COOLDOWN: f32 = 0.75;
+const ARCHER_QUEEN_ALTAR_HERO_ATTACK_RANGE: f32 = 5.0;
+const ARCHER_QUEEN_ALTAR_HERO_PROJECTILE_SPEED: f32 = 15.0;
+
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+pub struct ArcherQueenAltarModel {
+    /// Level of defending hero.
+    pub level: UsizeWithMax<ARCHER_QUEEN_ALTAR_LEVEL_INDEX_MAX>,
+}
+
+impl BuildingModel for ArcherQueenAltarModel {
+    fn r#type(&self) -> &'static BuildingType {
+        &ARCHER_QUEEN_ALTAR
+    }
+
+    fn spawn(&self, world: &mut World, position: Vector2<usize>) {
+        let level = &ARCHER_QUEEN_ALTAR_LEVELS[*self.level];
+
+        spawn_hero_altar(
+            world,
+            ARCHER_QUEEN_ALTAR_HEALTH,
+            position,
+            ARCHER_QUEEN_ALTAR.size,
+            DefendingHero {
+                health: level.hero_health,
+                speed: ARCHER_QUEEN_ALTAR_HERO_SPEED,
+                attack_cooldown: ARCHER_QUEEN_ALTAR_HERO_ATTACK_COOLDOWN,
+                attack_range: ARCHER_QUEEN_ALTAR_HERO_ATTACK_RANGE,
+                attack: Box::new(TargetProjectileAttack {
+                    damage: level.hero_attack_damage,
+                    projectile_speed: ARCHER_QUEEN_ALTAR_HERO_PROJECTILE_SPEED,
+                }),
+                attack_air: true,
+                altar_range: ARCHER_QUEEN_ALTAR_RANGE,
+                color: ShapeColor::new(200, 0, 255),
+            },
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use arbitrary::Arbitrary;
+use hecs::World;
+use nalgebra::Vector2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    ShapeColor,
+    UsizeWithMax,
+    buildings::{
+        BuildingModel,
+        BuildingType,
+        utils::hero_altar::{
+            DefendingHero,
+            spawn_hero_altar,
+        },
+    },
+    game::features::actions::MeleeAttack,
+};
+
+struct BarbarianKingAltarLevel {
+    pub hero_health: f32,
+    pub hero_attack_damage: f32,
+}
+
+const BARBARIAN_KING_ALTAR_LEVELS_LEN: usize = 10;
+const BARBARIAN_KING_ALTAR_LEVEL_INDEX_MAX: usize = BARBARIAN_KING_ALTAR_LEVELS_LEN - 1;
+const BARBARIAN_KING_ALTAR_LEVELS: [BarbarianKingAltarLevel; BARBARIAN_KING_ALTAR_LEVELS_LEN] = [
+    BarbarianKingAltarLevel {
+        hero_health: 1445.0,
+        hero_attack_damage: 122.4,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1481.0,
+        hero_attack_damage: 124.8,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1518.0,
+        hero_attack_damage: 126.0,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1556.0,
+        hero_attack_damage: 128.4,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1595.0,
+        hero_attack_damage: 130.8,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1635.0,
+        hero_attack_damage: 133.2,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1675.0,
+        hero_attack_damage: 135.6,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1717.0,
+        hero_attack_damage: 138.0,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1760.0,
+        hero_attack_damage: 140.4,
+    },
+    BarbarianKingAltarLevel {
+        hero_health: 1805.0,
+        hero_attack_damage: 142.8,
+    },
+];
+
+const BARBARIAN_KING_ALTAR: BuildingType = BuildingType {
+    name: "BarbarianKingAltar",
+    size: Vector2::new(3, 3),
+    levels: BARBARIAN_KING_ALTAR_LEVELS.len(),
+    options: &[],
+    affects_drop_zone: true,
+};
+
+inventory::submit! {BARBARIAN_KING_ALTAR}
+
+const BARBARIAN_KING_ALTAR_HEALTH: f32 = 250.0;
+const BARBARIAN_KING_ALTAR_RANGE: f32 = 9.0;
+const BARBARIAN_KING_ALTAR_HERO_SPEED: f32 = 2.0;
+const BARBARIAN_KING_ALTAR_HERO_ATTACK_COOLDOWN: f32 = 1.2;
+const BARBARIAN_KING_ALTAR_HERO_ATTACK_RANGE: f32 = 1.0;
+
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+pub struct BarbarianKingAltarModel {
+    /// Level of defending hero.
+    pub level: UsizeWithMax<BARBARIAN_KING_ALTAR_LEVEL_INDEX_MAX>,
+}
+
+impl BuildingModel for BarbarianKingAltarModel {
+    fn r#type(&self) -> &'static BuildingType {
+        &BARBARIAN_KING_ALTAR
+    }
+
+    fn spawn(&self, world: &mut World, position: Vector2<usize>) {
+        let level = &BARBARIAN_KING_ALTAR_LEVELS[*self.level];
+
+        spawn_hero_altar(
+            world,
+            BARBARIAN_KING_ALTAR_HEALTH,
+            position,
+            BARBARIAN_KING_ALTAR.size,
+            DefendingHero {
+                health: level.hero_health,
+                speed: BARBARIAN_KING_ALTAR_HERO_SPEED,
+                attack_cooldown: BARBARIAN_KING_ALTAR_HERO_ATTACK_COOLDOWN,
+                attack_range: BARBARIAN_KING_ALTAR_HERO_ATTACK_RANGE,
+                attack: Box::new(MeleeAttack {
+                    damage: level.hero_attack_damage,
+                }),
+                attack_air: false,
+                altar_range: BARBARIAN_KING_ALTAR_RANGE,
+                color: ShapeColor::new(255, 170, 0),
+            },
+        );
+    }
+}
@@ -98,6 +98,7 @@ impl BuildingModel for ClanCastleModel {
                     unit_deploy_cooldown: CLAN_CASTLE_UNIT_DEPLOY_COOLDOWN,
                     remaining_unit_deploy_cooldown: 0.0,
                     unit_deploy_trigger_range: CLAN_CASTLE_UNIT_DEPLOY_TRIGGER_RANGE,
+                    lure_targets: Vec::new(),
                 },
             )
             .unwrap();
@@ -0,0 +1,124 @@
+use arbitrary::Arbitrary;
+use hecs::World;
+use nalgebra::Vector2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    ShapeColor,
+    UsizeWithMax,
+    buildings::{
+        BuildingModel,
+        BuildingType,
+        utils::hero_altar::{
+            DefendingHero,
+            spawn_hero_altar,
+        },
+    },
+    game::features::actions::TargetProjectileAttack,
+};
+
+struct GrandWardenAltarLevel {
+    pub hero_health: f32,
+    pub hero_attack_damage: f32,
+}
+
+const GRAND_WARDEN_ALTAR_LEVELS_LEN: usize = 10;
+const GRAND_WARDEN_ALTAR_LEVEL_INDEX_MAX: usize = GRAND_WARDEN_ALTAR_LEVELS_LEN - 1;
+const GRAND_WARDEN_ALTAR_LEVELS: [GrandWardenAltarLevel; GRAND_WARDEN_ALTAR_LEVELS_LEN] = [
+    GrandWardenAltarLevel {
+        hero_health: 1000.0,
+        hero_attack_damage: 126.0,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1021.0,
+        hero_attack_damage: 136.8,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1042.0,
+        hero_attack_damage: 147.6,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1064.0,
+        hero_attack_damage: 158.4,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1086.0,
+        hero_attack_damage: 169.2,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1108.0,
+        hero_attack_damage: 181.8,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1131.0,
+        hero_attack_damage: 194.4,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1155.0,
+        hero_attack_damage: 208.8,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1178.0,
+        hero_attack_damage: 225.0,
+    },
+    GrandWardenAltarLevel {
+        hero_health: 1203.0,
+        hero_attack_damage: 243.0,
+    },
+];
+
+const GRAND_WARDEN_ALTAR: BuildingType = BuildingType {
+    name: "GrandWardenAltar",
+    size: Vector2::new(3, 3),
+    levels: GRAND_WARDEN_ALTAR_LEVELS.len(),
+    options: &[],
+    affects_drop_zone: true,
+};
+
+inventory::submit! {GRAND_WARDEN_ALTAR}
+
+const GRAND_WARDEN_ALTAR_HEALTH: f32 = 250.0;
+const GRAND_WARDEN_ALTAR_RANGE: f32 = 9.0;
+const GRAND_WARDEN_ALTAR_HERO_SPEED: f32 = 2.0;
+const GRAND_WARDEN_ALTAR_HERO_ATTACK_COOLDOWN: f32 = 1.8;
+const GRAND_WARDEN_ALTAR_HERO_ATTACK_RANGE: f32 = 7.0;
+const GRAND_WARDEN_ALTAR_HERO_PROJECTILE_SPEED: f32 = 15.0;
+
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+pub struct GrandWardenAltarModel {
+    /// Level of defending hero.
+    pub level: UsizeWithMax<GRAND_WARDEN_ALTAR_LEVEL_INDEX_MAX>,
+}
+
+impl BuildingModel for GrandWardenAltarModel {
+    fn r#type(&self) -> &'static BuildingType {
+        &GRAND_WARDEN_ALTAR
+    }
+
+    fn spawn(&self, world: &mut World, position: Vector2<usize>) {
+        let level = &GRAND_WARDEN_ALTAR_LEVELS[*self.level];
+
+        spawn_hero_altar(
+            world,
+            GRAND_WARDEN_ALTAR_HEALTH,
+            position,
+            GRAND_WARDEN_ALTAR.size,
+            DefendingHero {
+                health: level.hero_health,
+                speed: GRAND_WARDEN_ALTAR_HERO_SPEED,
+                attack_cooldown: GRAND_WARDEN_ALTAR_HERO_ATTACK_COOLDOWN,
+                attack_range: GRAND_WARDEN_ALTAR_HERO_ATTACK_RANGE,
+                attack: Box::new(TargetProjectileAttack {
+                    damage: level.hero_attack_damage,
+                    projectile_speed: GRAND_WARDEN_ALTAR_HERO_PROJECTILE_SPEED,
+                }),
+                attack_air: true,
+                altar_range: GRAND_WARDEN_ALTAR_RANGE,
+                color: ShapeColor::new(0, 200, 255),
+            },
+        );
+    }
+}
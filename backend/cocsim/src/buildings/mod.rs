@@ -1,8 +1,10 @@
 mod air_bomb;
 mod air_defense;
 mod air_sweeper;
+mod archer_queen_altar;
 mod archer_tower;
 mod army_camp;
+mod barbarian_king_altar;
 mod barracks;
 mod bomb_tower;
 mod builders_hut;
@@ -15,6 +17,7 @@ mod elixir_storage;
 mod goblin_hut;
 mod gold_mine;
 mod gold_storage;
+mod grand_warden_altar;
 mod hidden_tesla;
 mod laboratory;
 mod mortar;
@@ -29,8 +32,10 @@ pub use air_bomb::*;
 pub use air_defense::*;
 pub use air_sweeper::*;
 use arbitrary::Arbitrary;
+pub use archer_queen_altar::*;
 pub use archer_tower::*;
 pub use army_camp::*;
+pub use barbarian_king_altar::*;
 pub use barracks::*;
 pub use bomb_tower::*;
 pub use builders_hut::*;
@@ -44,6 +49,7 @@ use enum_dispatch::enum_dispatch;
 pub use goblin_hut::*;
 pub use gold_mine::*;
 pub use gold_storage::*;
+pub use grand_warden_altar::*;
 use hecs::World;
 pub use hidden_tesla::*;
 pub use laboratory::*;
@@ -97,10 +103,14 @@ pub enum BuildingModelEnum {
     AirDefenseModel,
     #[serde(rename = "AirSweeper")]
     AirSweeperModel,
+    #[serde(rename = "ArcherQueenAltar")]
+    ArcherQueenAltarModel,
     #[serde(rename = "ArmyCamp")]
     ArmyCampModel,
     #[serde(rename = "ArcherTower")]
     ArcherTowerModel,
+    #[serde(rename = "BarbarianKingAltar")]
+    BarbarianKingAltarModel,
     #[serde(rename = "Barracks")]
     BarracksModel,
     #[serde(rename = "BombTower")]
@@ -125,6 +135,8 @@ pub enum BuildingModelEnum {
     GoldMineModel,
     #[serde(rename = "GoldStorage")]
     GoldStorageModel,
+    #[serde(rename = "GrandWardenAltar")]
+    GrandWardenAltarModel,
     #[serde(rename = "HiddenTesla")]
     HiddenTeslaModel,
     #[serde(rename = "Laboratory")]
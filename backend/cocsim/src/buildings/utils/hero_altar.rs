@@ -0,0 +1,108 @@
+use hecs::{
+    Entity,
+    World,
+};
+use nalgebra::Vector2;
+
+use crate::{
+    Shape,
+    ShapeColor,
+    buildings::utils::other_building::spawn_other_building,
+    colliders::PointCollider,
+    game::features::{
+        actions::Action,
+        attack::{
+            AttackTarget,
+            AttackTargetFlags,
+            Attacker,
+            FalseRetargetCondition,
+            Team,
+            targeting::hero::HeroFindTarget,
+        },
+        damage::DamageMultiplier,
+        drawable::Drawable,
+        health::Health,
+        mover::Mover,
+        pathfinding::Pathfinder,
+        position::Position,
+        speed::Speed,
+        unit::Unit,
+    },
+};
+
+const HERO_HOUSING_SPACE: usize = 25;
+
+pub struct DefendingHero {
+    pub health: f32,
+    pub speed: f32,
+    pub attack_cooldown: f32,
+    pub attack_range: f32,
+    pub attack: Box<dyn Action>,
+    pub attack_air: bool,
+    /// Hero attacks units only inside of this range around altar.
+    pub altar_range: f32,
+    pub color: ShapeColor,
+}
+
+/// Spawns altar building and defending hero standing on it. Returns altar id.
+pub fn spawn_hero_altar(
+    world: &mut World,
+    altar_health: f32,
+    position: Vector2<usize>,
+    size: Vector2<usize>,
+    hero: DefendingHero,
+) -> Entity {
+    let altar_id = spawn_other_building(world, altar_health, position, size);
+    let altar_position = world.get::<&Position>(altar_id).unwrap().0;
+
+    world.spawn((
+        Position(altar_position),
+        Unit {
+            housing_space: HERO_HOUSING_SPACE,
+        },
+        DamageMultiplier { value: 1.0 },
+        Speed {
+            raw: hero.speed,
+            real: hero.speed,
+        },
+        Mover {
+            arrived: true,
+            target: altar_position,
+            waypoints: Vec::new(),
+        },
+        Pathfinder::default(),
+        Health {
+            health: hero.health,
+            max_health: hero.health,
+            incoming_damage: 0.0,
+        },
+        Team::Defense,
+        Attacker {
+            attack_cooldown: hero.attack_cooldown,
+            remaining_attack_cooldown: hero.attack_cooldown,
+            target: Entity::DANGLING,
+            retarget_condition: FalseRetargetCondition.into(),
+            retarget: true,
+            attack: hero.attack,
+        },
+        AttackTarget {
+            collider: PointCollider::zero().into(),
+            flags: AttackTargetFlags::UNIT | AttackTargetFlags::GROUND,
+        },
+        HeroFindTarget {
+            altar_position,
+            altar_range: hero.altar_range,
+            attack_range: hero.attack_range,
+            attack_air: hero.attack_air,
+            attack_ground: true,
+        },
+        Drawable::Shapes(vec![Shape::Circle {
+            x: 0.0,
+            y: 0.0,
+            radius: 0.3,
+            color: hero.color,
+        }]),
+    ));
+
+    altar_id
+}
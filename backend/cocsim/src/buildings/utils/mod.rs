@@ -1,4 +1,5 @@
 pub mod defensive_building;
+pub mod hero_altar;
 pub mod other_building;
 pub mod resource_building;
 pub mod trap;
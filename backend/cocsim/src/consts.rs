@@ -32,11 +32,18 @@ pub const MAX_ATTACK_DURATION: f32 = 180.0;
 
 /// attack_optimizer
 pub const MAX_UNIT_DROP_TIME: f32 = 20.0;
+/// Max length (in tiles) of line, arc or split unit group formation.
+pub const MAX_UNIT_GROUP_SPREAD: f32 = 10.0;
 pub const POPULATION_SIZE: usize = 20;
 pub const NEW_POPULATION_SIZE: usize = 40;
 pub const NEW_RANDOM_PLANS: usize = 5;
 pub const ATTACK_PLAN_EXECUTOR_TPS: usize = 15;
 pub const ATTACK_PLAN_EXECUTIONS_COUNT: usize = 20;
+/// Executions of every plan in the first round of racing.
+pub const ATTACK_PLAN_RACING_INITIAL_EXECUTIONS_COUNT: usize = 3;
+/// Confidence interval of racing plan score is this many standard errors
+/// wide in each direction.
+pub const ATTACK_PLAN_RACING_CONFIDENCE: f32 = 2.0;
 pub const RNG_INITIAL_STATE: u128 = 0x28eccc9e8da2792e12f88fb222616a86;
 
 pub const SHAPE_SERIALIZE_ROUND_DIGITS: u32 = 2;
@@ -0,0 +1,216 @@
+use anyhow::ensure;
+use rand_pcg::Pcg64Mcg;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        AttackOptimizerParams,
+        InitialAttackPlan,
+        new_attack_optimizer,
+    },
+    consts::RNG_INITIAL_STATE,
+    defense_optimizer::{
+        DefenseStats,
+        evaluate_defense,
+        mutate_layout,
+    },
+};
+
+const ATTACK_OPTIMIZER: &str = "v1-random";
+const ATTACK_OPTIMIZER_PLANS_PER_STEP: usize = 20;
+const ATTACK_OPTIMIZER_STEPS: usize = 5;
+const REOPTIMIZE_INTERVAL: usize = 5;
+const LAYOUTS_PER_STEP: usize = 10;
+const MAX_MOVE_DISTANCE: usize = 3;
+
+/// Parameters of [`DefenseLayoutOptimizer`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DefenseLayoutOptimizerParams {
+    /// One of [`crate::attack_optimizer::ATTACK_OPTIMIZER_NAMES`], plan of
+    /// each army is optimized with it.
+    pub attack_optimizer: String,
+    pub attack_optimizer_params: AttackOptimizerParams,
+    /// Steps of each attack optimization.
+    pub attack_optimizer_steps: usize,
+    /// Attacks are optimized against current layout every this many steps,
+    /// layout is improved during the rest of them.
+    pub reoptimize_interval: usize,
+    /// Candidate layouts evaluated per layout step.
+    pub layouts_per_step: usize,
+    /// Max distance in tiles building or wall segment is moved by along each
+    /// axis.
+    pub max_move_distance: usize,
+}
+
+impl Default for DefenseLayoutOptimizerParams {
+    fn default() -> Self {
+        Self {
+            attack_optimizer: ATTACK_OPTIMIZER.to_owned(),
+            attack_optimizer_params: AttackOptimizerParams {
+                plans_per_step: Some(ATTACK_OPTIMIZER_PLANS_PER_STEP),
+                ..Default::default()
+            },
+            attack_optimizer_steps: ATTACK_OPTIMIZER_STEPS,
+            reoptimize_interval: REOPTIMIZE_INTERVAL,
+            layouts_per_step: LAYOUTS_PER_STEP,
+            max_move_distance: MAX_MOVE_DISTANCE,
+        }
+    }
+}
+
+impl DefenseLayoutOptimizerParams {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in [
+            ("attackOptimizerSteps", self.attack_optimizer_steps),
+            ("reoptimizeInterval", self.reoptimize_interval),
+            ("layoutsPerStep", self.layouts_per_step),
+            ("maxMoveDistance", self.max_move_distance),
+        ] {
+            ensure!(value > 0, "Defense parameter {name} should be positive");
+        }
+
+        self.attack_optimizer_params.validate()
+    }
+}
+
+/// Moves buildings of base to minimize expected stars of the best attack.
+/// Attacks of each army are optimized against current layout every
+/// [`DefenseLayoutOptimizerParams::reoptimize_interval`] steps, starting from
+/// their previous plans. During the rest of steps layouts mutated by
+/// [`mutate_layout`] are evaluated against these plans, and the best one is
+/// taken if it isn't worse than current layout.
+///
+/// Layout keeps [`crate::Map::trap_zones`] and drop zone rules, but map
+/// variants (see [`ValidatedMap::from_variants`]) are not supported: they are
+/// dropped.
+pub struct DefenseLayoutOptimizer {
+    map: ValidatedMap,
+    armies: Vec<Army>,
+    config: GameConfig,
+    params: DefenseLayoutOptimizerParams,
+    /// The best plan of each army found so far, against current or previous
+    /// layouts.
+    plans: Vec<InitialAttackPlan>,
+    stats: Option<DefenseStats>,
+    steps: usize,
+    rng: Pcg64Mcg,
+}
+
+impl DefenseLayoutOptimizer {
+    pub fn new(
+        map: ValidatedMap,
+        armies: Vec<Army>,
+        config: GameConfig,
+        params: DefenseLayoutOptimizerParams,
+    ) -> anyhow::Result<Self> {
+        ensure!(!armies.is_empty(), "At least one army expected");
+
+        params.validate()?;
+
+        Ok(Self {
+            map: ValidatedMap::new((*map).clone(), map.drop_zone_rules())?,
+            plans: vec![InitialAttackPlan::default(); armies.len()],
+            armies,
+            config,
+            params,
+            stats: None,
+            steps: 0,
+            rng: Pcg64Mcg::new(RNG_INITIAL_STATE),
+        })
+    }
+
+    /// Current layout and its stats, [`None`] if [`Self::step`] wasn't called
+    /// yet. It's the best layout against the latest attack plans, layouts of
+    /// previous steps were evaluated against weaker plans and aren't compared
+    /// with it.
+    pub fn current(&self) -> Option<(&ValidatedMap, &DefenseStats)> {
+        self.stats.as_ref().map(|stats| (&self.map, stats))
+    }
+
+    /// The best plan of each army, in armies order.
+    pub fn attack_plans(&self) -> &[InitialAttackPlan] {
+        &self.plans
+    }
+
+    pub fn step(&mut self) -> anyhow::Result<()> {
+        if self.steps.is_multiple_of(self.params.reoptimize_interval) {
+            self.optimize_attacks()?;
+        } else {
+            self.improve_layout()?;
+        }
+
+        self.steps += 1;
+
+        Ok(())
+    }
+
+    fn optimize_attacks(&mut self) -> anyhow::Result<()> {
+        for (army, plan) in self.armies.iter().zip(&mut self.plans) {
+            let mut optimizer = new_attack_optimizer(
+                &self.params.attack_optimizer,
+                &self.params.attack_optimizer_params,
+                self.params.attack_optimizer_steps,
+                self.map.clone(),
+                army.clone(),
+                None,
+                self.config.clone(),
+                (self.steps > 0).then_some(&*plan),
+            )?;
+
+            for _ in 0..self.params.attack_optimizer_steps {
+                optimizer.step();
+            }
+
+            let (actions, _) = optimizer.best().expect("Best plan exists here");
+
+            *plan = InitialAttackPlan::from_executor_actions(&actions);
+        }
+
+        self.stats = Some(evaluate_defense(
+            &self.map,
+            &self.armies,
+            &self.plans,
+            &self.config,
+        )?);
+
+        Ok(())
+    }
+
+    fn improve_layout(&mut self) -> anyhow::Result<()> {
+        let mut best = None::<(ValidatedMap, DefenseStats)>;
+
+        for _ in 0..self.params.layouts_per_step {
+            let Some(layout) =
+                mutate_layout(&self.map, self.params.max_move_distance, &mut self.rng)
+            else {
+                continue;
+            };
+            let stats = evaluate_defense(&layout, &self.armies, &self.plans, &self.config)?;
+
+            if best
+                .as_ref()
+                .is_none_or(|(_, best_stats)| stats.compare(best_stats).is_lt())
+            {
+                best = Some((layout, stats));
+            }
+        }
+
+        if let Some((layout, stats)) = best
+            && stats
+                .compare(self.stats.as_ref().expect("Attacks are optimized first"))
+                .is_le()
+        {
+            self.map = layout;
+            self.stats = Some(stats);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        AttackPlanExecutionStats,
+        InitialAttackPlan,
+        execute_attack_plan,
+    },
+    consts::{
+        ATTACK_PLAN_EXECUTIONS_COUNT,
+        ATTACK_PLAN_EXECUTOR_TPS,
+    },
+};
+
+/// How layout holds against the best known plan of each army.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenseStats {
+    /// Stats of each army's plan, in armies order.
+    pub attacks: Vec<AttackPlanExecutionStats>,
+    /// Index of attack with the most expected stars, ties are broken by
+    /// destruction, then by time.
+    pub best_attack: usize,
+}
+
+impl DefenseStats {
+    fn new(attacks: Vec<AttackPlanExecutionStats>) -> Self {
+        let best_attack = (0..attacks.len())
+            .max_by(|a, b| compare_attacks(&attacks[*a], &attacks[*b]))
+            .expect("At least one attack expected");
+
+        Self {
+            attacks,
+            best_attack,
+        }
+    }
+
+    /// Expected stars of the best attack.
+    pub fn expected_stars(&self) -> f32 {
+        self.attacks[self.best_attack].avg_stars
+    }
+
+    /// Average destruction of the best attack.
+    pub fn percentage_destroyed(&self) -> f32 {
+        self.attacks[self.best_attack].avg_percentage_destroyed
+    }
+
+    /// [`Ordering::Less`] if `self` is better defense than `other`: its best
+    /// attack gets less stars, the same stars with less destruction or the
+    /// same destruction takes longer.
+    pub fn compare(&self, other: &Self) -> Ordering {
+        compare_attacks(
+            &self.attacks[self.best_attack],
+            &other.attacks[other.best_attack],
+        )
+    }
+}
+
+/// [`Ordering::Greater`] if attack `a` is better than `b`: it gets more stars,
+/// the same stars with more destruction or the same destruction faster.
+fn compare_attacks(a: &AttackPlanExecutionStats, b: &AttackPlanExecutionStats) -> Ordering {
+    a.avg_stars
+        .total_cmp(&b.avg_stars)
+        .then(
+            a.avg_percentage_destroyed
+                .total_cmp(&b.avg_percentage_destroyed),
+        )
+        .then(b.avg_time_elapsed.total_cmp(&a.avg_time_elapsed))
+}
+
+/// Executes plan of each army on `map`.
+pub fn evaluate_defense(
+    map: &ValidatedMap,
+    armies: &[Army],
+    plans: &[InitialAttackPlan],
+    config: &GameConfig,
+) -> anyhow::Result<DefenseStats> {
+    let attacks = armies
+        .iter()
+        .zip(plans)
+        .map(|(army, plan)| {
+            Ok(execute_attack_plan(
+                map,
+                config,
+                &plan.clamped_executor_actions(army, map)?,
+                ATTACK_PLAN_EXECUTIONS_COUNT,
+                ATTACK_PLAN_EXECUTOR_TPS,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(DefenseStats::new(attacks))
+}
@@ -0,0 +1,13 @@
+mod defense_layout_optimizer;
+mod defense_stats;
+mod mutate_layout;
+
+pub use defense_layout_optimizer::{
+    DefenseLayoutOptimizer,
+    DefenseLayoutOptimizerParams,
+};
+pub use defense_stats::{
+    DefenseStats,
+    evaluate_defense,
+};
+pub use mutate_layout::mutate_layout;
@@ -0,0 +1,57 @@
+use nalgebra::Vector2;
+use rand::Rng;
+
+use crate::ValidatedMap;
+
+/// Attempts to find valid mutation before giving up.
+const MUTATION_ATTEMPTS: usize = 20;
+
+/// Layout with one random mutation: building moved by at most `max_distance`
+/// tiles along each axis, two buildings of the same size swapped, or straight
+/// wall segment moved by at most `max_distance` tiles. [`None`] if no valid
+/// mutation was found.
+pub fn mutate_layout(
+    map: &ValidatedMap,
+    max_distance: usize,
+    rng: &mut impl Rng,
+) -> Option<ValidatedMap> {
+    let max_distance = max_distance as i32;
+    let mut layout = map.clone();
+
+    for _ in 0..MUTATION_ATTEMPTS {
+        let i = rng.random_range(0..layout.buildings.len());
+        let offset = Vector2::new(
+            rng.random_range(-max_distance..=max_distance) as isize,
+            rng.random_range(-max_distance..=max_distance) as isize,
+        );
+        let mutation = rng.random_range(0..3);
+
+        if mutation != 1 && offset == Vector2::zeros() {
+            continue;
+        }
+
+        let result = match mutation {
+            0 => {
+                let building = &layout.buildings[i];
+                let (Some(x), Some(y)) = (
+                    building.x.checked_add_signed(offset.x),
+                    building.y.checked_add_signed(offset.y),
+                ) else {
+                    continue;
+                };
+
+                layout.move_building(i, Vector2::new(x, y))
+            }
+            1 => layout.swap_buildings(i, rng.random_range(0..layout.buildings.len())),
+            _ => layout
+                .wall_segment(i, rng.random())
+                .and_then(|walls| layout.move_walls(&walls, offset)),
+        };
+
+        if result.is_ok() {
+            return Some(layout);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use arbitrary::Arbitrary;
+use nalgebra::{
+    DMatrix,
+    Vector2,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::buildings::{
+    BuildingModel,
+    BuildingModelEnum,
+};
+
+/// How drop zone is computed from buildings. Presets for different game
+/// versions are provided as associated constants, [`Default`] is
+/// [`Self::CLASSIC`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DropZoneRules {
+    /// Tiles not farther than this (in tiles, diagonal counts as 1) from
+    /// building which affects drop zone are blocked. Building tiles are always
+    /// blocked.
+    pub building_margin: usize,
+    /// Same as [`Self::building_margin`], but for walls.
+    pub wall_margin: usize,
+    /// Border tiles are in drop zone even if they are next to buildings.
+    pub allow_border: bool,
+}
+
+impl DropZoneRules {
+    /// 8 neighbours of every building (including walls) are blocked.
+    pub const CLASSIC: Self = Self {
+        building_margin: 1,
+        wall_margin: 1,
+        allow_border: false,
+    };
+    /// Walls block only their own tiles, border is always available.
+    pub const MODERN: Self = Self {
+        building_margin: 1,
+        wall_margin: 0,
+        allow_border: true,
+    };
+
+    /// Margin of tiles blocked around each tile of building, [`None`] if
+    /// building doesn't affect drop zone.
+    pub(crate) fn margin(&self, model: &BuildingModelEnum) -> Option<usize> {
+        if !model.r#type().affects_drop_zone {
+            None
+        } else if let BuildingModelEnum::WallModel(_) = model {
+            Some(self.wall_margin)
+        } else {
+            Some(self.building_margin)
+        }
+    }
+}
+
+impl Default for DropZoneRules {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+/// Drop zone rules preset selected by [`crate::Map`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Arbitrary)]
+#[serde(rename_all = "camelCase")]
+pub enum DropZoneRulesPreset {
+    /// [`DropZoneRules::CLASSIC`].
+    #[default]
+    Classic,
+    /// [`DropZoneRules::MODERN`].
+    Modern,
+}
+
+impl DropZoneRulesPreset {
+    pub fn rules(self) -> &'static DropZoneRules {
+        match self {
+            Self::Classic => &DropZoneRules::CLASSIC,
+            Self::Modern => &DropZoneRules::MODERN,
+        }
+    }
+
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Closed polygon going along tile edges between blocked tiles (on the left
+/// side) and drop zone (on the right side). Edge of the map counts as drop
+/// zone.
+#[derive(Debug, Clone)]
+pub struct DropZonePolygon {
+    vertices: Vec<Vector2<f32>>,
+    /// `distances[i]` is distance from first vertex to `vertices[i]` along
+    /// polygon. Last value is perimeter.
+    distances: Vec<f32>,
+}
+
+impl DropZonePolygon {
+    fn new(vertices: Vec<Vector2<f32>>) -> Self {
+        let mut distances = Vec::with_capacity(vertices.len() + 1);
+        let mut distance = 0.0;
+
+        distances.push(distance);
+
+        for i in 0..vertices.len() {
+            distance += vertices[i].metric_distance(&vertices[(i + 1) % vertices.len()]);
+            distances.push(distance);
+        }
+
+        Self {
+            vertices,
+            distances,
+        }
+    }
+
+    /// Vertices in order, last vertex is connected to the first one.
+    pub fn vertices(&self) -> &[Vector2<f32>] {
+        &self.vertices
+    }
+
+    pub fn perimeter(&self) -> f32 {
+        *self.distances.last().unwrap()
+    }
+
+    /// Point at `distance` from first vertex along polygon. `distance` wraps
+    /// around perimeter.
+    pub fn point_at(&self, distance: f32) -> Vector2<f32> {
+        let distance = distance.rem_euclid(self.perimeter());
+        let i = self
+            .distances
+            .partition_point(|vertex_distance| *vertex_distance <= distance)
+            .clamp(1, self.vertices.len())
+            - 1;
+        let a = self.vertices[i];
+        let b = self.vertices[(i + 1) % self.vertices.len()];
+
+        a + (b - a) * ((distance - self.distances[i]) / (self.distances[i + 1] - self.distances[i]))
+    }
+
+    /// Traces boundaries of blocked areas of `drop_zone`.
+    pub(crate) fn trace(drop_zone: &DMatrix<bool>) -> Vec<Self> {
+        let size = drop_zone.nrows() as i32;
+        let is_blocked = |x: i32, y: i32| {
+            0 <= x && x < size && 0 <= y && y < size && !drop_zone[(x as usize, y as usize)]
+        };
+
+        // directed unit edges with blocked tile on the left side
+        let mut edges: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
+
+        for x in 0..size {
+            for y in 0..size {
+                if !is_blocked(x, y) {
+                    continue;
+                }
+
+                let sides = [
+                    ((x, y - 1), (x, y), (1, 0)),
+                    ((x + 1, y), (x + 1, y), (0, 1)),
+                    ((x, y + 1), (x + 1, y + 1), (-1, 0)),
+                    ((x - 1, y), (x, y + 1), (0, -1)),
+                ];
+
+                for ((neighbor_x, neighbor_y), start, direction) in sides {
+                    if !is_blocked(neighbor_x, neighbor_y) {
+                        edges.entry(start).or_default().push(direction);
+                    }
+                }
+            }
+        }
+
+        let mut starts = edges.keys().copied().collect::<Vec<_>>();
+
+        // deterministic polygons order
+        starts.sort_unstable();
+
+        let mut result = Vec::new();
+
+        for start in starts {
+            while let Some(first_direction) = edges.get_mut(&start).and_then(Vec::pop) {
+                let mut vertices = vec![start];
+                let mut direction = first_direction;
+                let mut vertex = (start.0 + direction.0, start.1 + direction.1);
+
+                while vertex != start {
+                    let outgoing = edges.get_mut(&vertex).unwrap();
+                    // prefer turning to the blocked side, so diagonally touching
+                    // blocked areas get separate polygons
+                    let next_direction = [
+                        (-direction.1, direction.0),
+                        direction,
+                        (direction.1, -direction.0),
+                    ]
+                    .into_iter()
+                    .find(|candidate| outgoing.contains(candidate))
+                    .unwrap();
+
+                    outgoing.retain(|candidate| *candidate != next_direction);
+
+                    if next_direction != direction {
+                        vertices.push(vertex);
+                    }
+
+                    direction = next_direction;
+                    vertex = (vertex.0 + direction.0, vertex.1 + direction.1);
+                }
+
+                if direction == first_direction {
+                    // start vertex is in the middle of straight edge
+                    vertices.remove(0);
+                }
+
+                result.push(Self::new(
+                    vertices
+                        .into_iter()
+                        .map(|(x, y)| Vector2::new(x as f32, y as f32))
+                        .collect(),
+                ));
+            }
+        }
+
+        result
+    }
+}
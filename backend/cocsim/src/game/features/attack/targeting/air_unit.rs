@@ -33,10 +33,11 @@ pub trait TargetPrioritizer {
     ) -> bool;
 }
 
+/// Nearest unit or counted building.
 #[derive(Clone, Debug)]
-pub struct DragonTargetPrioritizer;
+pub struct NearestTargetPrioritizer;
 
-impl TargetPrioritizer for DragonTargetPrioritizer {
+impl TargetPrioritizer for NearestTargetPrioritizer {
     fn can_attack(&self, flags: AttackTargetFlags) -> bool {
         flags.is_unit() || flags.is_counted_building()
     }
@@ -81,7 +82,7 @@ impl TargetPrioritizer for DefensiveBuildingTargetPrioritizer {
 #[derive(Clone, Debug)]
 pub enum TargetPrioritizerEnum {
     DefensiveBuildingTargetPrioritizer,
-    DragonTargetPrioritizer,
+    NearestTargetPrioritizer,
 }
 
 pub struct AirUnitFindTarget {
@@ -121,8 +122,8 @@ pub fn update(game: &mut Game) {
                 // true when attacker has Stunned component
                 let attack_target = game.world.get::<&AttackTarget>(attacker.target).unwrap();
 
-                if !attack_target.flags.contains(AttackTargetFlags::AIR) {
-                    continue; // the following code updates mover.target if target is air unit
+                if !attack_target.flags.is_unit() {
+                    continue; // the following code updates mover.target if target is unit
                 }
 
                 let target_position = game.world.get::<&Position>(attacker.target).unwrap().0;
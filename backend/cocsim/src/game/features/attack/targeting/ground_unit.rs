@@ -0,0 +1,125 @@
+use hecs::{
+    Entity,
+    PreparedQuery,
+};
+
+use crate::{
+    Game,
+    colliders::Collider,
+    game::features::{
+        attack::{
+            AttackTarget,
+            AttackTargetFlags,
+            Attacker,
+            Team,
+            targeting::air_unit::{
+                TargetPrioritizer,
+                TargetPrioritizerEnum,
+            },
+        },
+        mover::Mover,
+        position::Position,
+    },
+    utils::AnyMapExt,
+};
+
+/// Same as [`super::air_unit::AirUnitFindTarget`], but for units with
+/// [`crate::game::features::pathfinding::Pathfinder`]. Only ground targets are
+/// considered. Distance to target is measured in straight line, path around
+/// buildings is found after target is chosen.
+pub struct GroundUnitFindTarget {
+    pub prioritizer: TargetPrioritizerEnum,
+    pub attack_range: f32,
+}
+
+#[derive(Default)]
+struct UpdateCache<'a> {
+    pub attacker_query: PreparedQuery<(
+        &'a GroundUnitFindTarget,
+        &'a mut Attacker,
+        &'a Team,
+        &'a Position,
+        &'a mut Mover,
+    )>,
+    pub target_query: PreparedQuery<(&'a AttackTarget, &'a Team, &'a Position)>,
+}
+
+pub fn update(game: &mut Game) {
+    let cache = game.cache.get_mut_or_default::<UpdateCache>();
+
+    for (
+        _attacker_id,
+        (ground_unit_find_target, attacker, attacker_team, attacker_position, attacker_mover),
+    ) in cache.attacker_query.query(&game.world).iter()
+    {
+        if !attacker.retarget {
+            if attacker.target != Entity::DANGLING {
+                // !attacker.retarget && attacker.target == Entity::DANGLING is
+                // true when attacker has Stunned component
+                let attack_target = game.world.get::<&AttackTarget>(attacker.target).unwrap();
+
+                if !attack_target.flags.is_unit() {
+                    continue; // the following code updates mover.target if target is unit
+                }
+
+                let target_position = game.world.get::<&Position>(attacker.target).unwrap().0;
+
+                attacker_mover.target = attack_target
+                    .collider
+                    .translate(target_position)
+                    .attack_area(ground_unit_find_target.attack_range)
+                    .nearest_point(attacker_position.0);
+            }
+
+            continue;
+        }
+
+        attacker.retarget = false;
+
+        let mut nearest_target: Option<(Entity, AttackTargetFlags, f32)> = None;
+
+        for (target_id, (attack_target, target_team, target_position)) in
+            cache.target_query.query(&game.world).iter()
+        {
+            if target_team == attacker_team
+                || !attack_target.flags.contains(AttackTargetFlags::GROUND)
+                || !ground_unit_find_target
+                    .prioritizer
+                    .can_attack(attack_target.flags)
+            {
+                continue;
+            }
+
+            let distance = attack_target
+                .collider
+                .translate(target_position.0)
+                .attack_area(ground_unit_find_target.attack_range)
+                .nearest_point(attacker_position.0)
+                .metric_distance(&attacker_position.0);
+
+            if nearest_target.is_none_or(|(_, nearest_flags, nearest_distance)| {
+                ground_unit_find_target.prioritizer.is_better(
+                    nearest_flags,
+                    nearest_distance,
+                    attack_target.flags,
+                    distance,
+                )
+            }) {
+                nearest_target = Some((target_id, attack_target.flags, distance));
+            }
+        }
+
+        if let Some((target_id, _, _)) = nearest_target {
+            attacker_mover.target = game
+                .world
+                .get::<&AttackTarget>(target_id)
+                .unwrap()
+                .collider
+                .translate(game.world.get::<&Position>(target_id).unwrap().0)
+                .attack_area(ground_unit_find_target.attack_range)
+                .random_near_point(attacker_position.0, &mut game.rng);
+            attacker_mover.arrived = false;
+            attacker.target = target_id;
+        }
+    }
+}
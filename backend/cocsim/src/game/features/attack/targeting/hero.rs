@@ -0,0 +1,169 @@
+use std::f32::consts::TAU;
+
+use hecs::{
+    Entity,
+    PreparedQuery,
+};
+use nalgebra::Vector2;
+use rand::Rng;
+
+use crate::{
+    Game,
+    colliders::{
+        Collider,
+        ColliderEnum,
+    },
+    game::features::{
+        attack::{
+            AttackTarget,
+            AttackTargetFlags,
+            Attacker,
+            Team,
+        },
+        mover::Mover,
+        position::Position,
+        stunned::Stunned,
+    },
+    utils::AnyMapExt,
+};
+
+/// Radius around altar in which idle defending hero walks.
+const HERO_PATROL_RANGE: f32 = 1.5;
+
+/// Targeting of defending hero. Hero attacks enemy units which can be reached
+/// without leaving circle of `altar_range` radius around altar and patrols near
+/// altar otherwise.
+pub struct HeroFindTarget {
+    pub altar_position: Vector2<f32>,
+    pub altar_range: f32,
+    pub attack_range: f32,
+    pub attack_air: bool,
+    pub attack_ground: bool,
+}
+
+impl HeroFindTarget {
+    fn can_attack(&self, flags: AttackTargetFlags) -> bool {
+        flags.is_unit()
+            && (self.attack_air && flags.contains(AttackTargetFlags::AIR)
+                || self.attack_ground && flags.contains(AttackTargetFlags::GROUND))
+    }
+
+    /// Returns point nearest to `hero_position` from which hero can attack
+    /// target with given `attack_area` without leaving altar range or [`None`]
+    /// if there is no such point.
+    fn attack_point(
+        &self,
+        attack_area: &ColliderEnum,
+        hero_position: Vector2<f32>,
+    ) -> Option<Vector2<f32>> {
+        for point in [hero_position, self.altar_position] {
+            let point = if attack_area.contains(point) {
+                point
+            } else {
+                attack_area.nearest_point(point)
+            };
+
+            if point.metric_distance(&self.altar_position) <= self.altar_range {
+                return Some(point);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Default)]
+struct UpdateCache<'a> {
+    pub attacker_query: PreparedQuery<(
+        &'a HeroFindTarget,
+        &'a mut Attacker,
+        &'a Team,
+        &'a Position,
+        &'a mut Mover,
+    )>,
+    pub target_query: PreparedQuery<(&'a AttackTarget, &'a Team, &'a Position)>,
+}
+
+pub fn update(game: &mut Game) {
+    let cache = game.cache.get_mut_or_default::<UpdateCache>();
+
+    for (
+        attacker_id,
+        (hero_find_target, attacker, attacker_team, attacker_position, attacker_mover),
+    ) in cache.attacker_query.query(&game.world).iter()
+    {
+        if game.world.satisfies::<&Stunned>(attacker_id).unwrap() {
+            continue;
+        }
+
+        let target_attack_point = |target: Entity| {
+            let attack_target = game.world.get::<&AttackTarget>(target).unwrap();
+            let target_position = game.world.get::<&Position>(target).unwrap().0;
+
+            hero_find_target.attack_point(
+                &attack_target
+                    .collider
+                    .translate(target_position)
+                    .attack_area(hero_find_target.attack_range),
+                attacker_position.0,
+            )
+        };
+
+        if !attacker.retarget
+            && game.world.contains(attacker.target)
+            && target_attack_point(attacker.target).is_none()
+        {
+            // target left altar range
+            attacker.retarget = true;
+        }
+
+        if attacker.retarget {
+            attacker.retarget = false;
+            attacker.target = Entity::DANGLING;
+
+            let mut nearest_target_distance = f32::INFINITY;
+
+            for (target_id, (attack_target, target_team, target_position)) in
+                cache.target_query.query(&game.world).iter()
+            {
+                if target_team == attacker_team || !hero_find_target.can_attack(attack_target.flags)
+                {
+                    continue;
+                }
+
+                let Some(attack_point) = hero_find_target.attack_point(
+                    &attack_target
+                        .collider
+                        .translate(target_position.0)
+                        .attack_area(hero_find_target.attack_range),
+                    attacker_position.0,
+                ) else {
+                    continue;
+                };
+
+                let distance = attack_point.metric_distance(&attacker_position.0);
+
+                if distance < nearest_target_distance {
+                    attacker.target = target_id;
+                    nearest_target_distance = distance;
+                }
+            }
+        }
+
+        if game.world.contains(attacker.target) {
+            attacker_mover.target = target_attack_point(attacker.target).unwrap();
+        } else if attacker_mover.arrived
+            || attacker_mover
+                .target
+                .metric_distance(&hero_find_target.altar_position)
+                > HERO_PATROL_RANGE
+        {
+            let angle = game.rng.random_range(0.0..TAU);
+            let distance = game.rng.random_range(0.0..=HERO_PATROL_RANGE);
+
+            attacker_mover.target =
+                hero_find_target.altar_position + Vector2::new(angle.cos(), angle.sin()) * distance;
+            attacker_mover.arrived = false;
+        }
+    }
+}
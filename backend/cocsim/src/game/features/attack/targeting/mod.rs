@@ -2,8 +2,12 @@ use crate::Game;
 
 pub mod air_unit;
 pub mod building;
+pub mod ground_unit;
+pub mod hero;
 
 pub fn update(game: &mut Game) {
     air_unit::update(game);
     building::update(game);
+    ground_unit::update(game);
+    hero::update(game);
 }
@@ -1,4 +1,7 @@
-use hecs::PreparedQuery;
+use hecs::{
+    Entity,
+    PreparedQuery,
+};
 use nalgebra::Vector2;
 
 use crate::{
@@ -9,6 +12,7 @@ use crate::{
         attack::{
             AttackTarget,
             AttackTargetFlags,
+            Attacker,
             Team,
         },
         position::Position,
@@ -29,6 +33,9 @@ pub struct ClanCastle {
     /// [`Self::unit_deploy_cooldown`]
     pub remaining_unit_deploy_cooldown: f32,
     pub unit_deploy_trigger_range: f32,
+    /// Enemies in order they entered [`Self::unit_deploy_trigger_range`].
+    /// Deployed unit chases first alive enemy it can attack (lure).
+    pub lure_targets: Vec<Entity>,
 }
 
 #[derive(Default)]
@@ -39,7 +46,7 @@ struct ClanCastleUpdateCache<'a> {
 
 pub fn update(game: &mut Game) {
     let cache = game.cache.get_mut_or_default::<ClanCastleUpdateCache>();
-    let mut units_to_spawn: Vec<(UnitModelEnum, Vector2<f32>, Team)> = Vec::new();
+    let mut units_to_spawn: Vec<(UnitModelEnum, Vector2<f32>, Team, Entity)> = Vec::new();
 
     for (_clan_castle_id, (clan_castle, clan_castle_position, clan_castle_team)) in
         cache.clan_castle_query.query(&game.world).iter()
@@ -48,19 +55,10 @@ pub fn update(game: &mut Game) {
             continue;
         }
 
-        clan_castle.remaining_unit_deploy_cooldown -= game.delta_time;
-
-        if clan_castle.remaining_unit_deploy_cooldown > 0.0 {
-            continue;
-        }
-
-        let mut has_air_target = false;
-        let mut has_ground_target = false;
-
-        for (_target_id, (attack_target, target_position, target_team)) in
+        for (target_id, (attack_target, target_position, target_team)) in
             cache.target_query.query(&game.world).iter()
         {
-            if target_team == clan_castle_team {
+            if target_team == clan_castle_team || clan_castle.lure_targets.contains(&target_id) {
                 continue;
             }
 
@@ -70,42 +68,50 @@ pub fn update(game: &mut Game) {
                 .attack_area(clan_castle.unit_deploy_trigger_range + UNIT_DISTANCE_TO_WAYPOINT_EPS)
                 .contains(clan_castle_position.0);
 
-            if !target_in_trigger_range {
-                continue;
+            if target_in_trigger_range {
+                clan_castle.lure_targets.push(target_id);
             }
+        }
 
-            if attack_target.flags.contains(AttackTargetFlags::AIR) {
-                has_air_target = true;
-            }
+        clan_castle
+            .lure_targets
+            .retain(|target_id| game.world.contains(*target_id));
 
-            if attack_target.flags.contains(AttackTargetFlags::GROUND) {
-                has_ground_target = true;
-            }
+        clan_castle.remaining_unit_deploy_cooldown -= game.delta_time;
 
-            if has_air_target && has_ground_target {
-                // no need to check for targets further
-                break;
-            }
+        if clan_castle.remaining_unit_deploy_cooldown > 0.0 {
+            continue;
         }
 
-        for i in (0..clan_castle.units.len()).rev() {
-            if has_air_target && clan_castle.units[i].r#type().attack_air
-                || has_ground_target && clan_castle.units[i].r#type().attack_ground
-            {
-                units_to_spawn.push((
-                    clan_castle.units.remove(i),
-                    clan_castle_position.0,
-                    *clan_castle_team,
-                ));
+        'units: for i in (0..clan_castle.units.len()).rev() {
+            let unit_type = clan_castle.units[i].r#type();
+
+            for target_id in &clan_castle.lure_targets {
+                let target_flags = game.world.get::<&AttackTarget>(*target_id).unwrap().flags;
 
-                clan_castle.remaining_unit_deploy_cooldown = clan_castle.unit_deploy_cooldown;
+                if unit_type.attack_air && target_flags.contains(AttackTargetFlags::AIR)
+                    || unit_type.attack_ground && target_flags.contains(AttackTargetFlags::GROUND)
+                {
+                    units_to_spawn.push((
+                        clan_castle.units.remove(i),
+                        clan_castle_position.0,
+                        *clan_castle_team,
+                        *target_id,
+                    ));
 
-                break;
+                    clan_castle.remaining_unit_deploy_cooldown = clan_castle.unit_deploy_cooldown;
+
+                    break 'units;
+                }
             }
         }
     }
 
-    for (unit_model, unit_position, unit_team) in units_to_spawn {
-        unit_model.spawn(&mut game.world, unit_position, unit_team);
+    for (unit_model, unit_position, unit_team, lure_target) in units_to_spawn {
+        let unit_id = unit_model.spawn(&mut game.world, unit_position, unit_team);
+        let mut attacker = game.world.get::<&mut Attacker>(unit_id).unwrap();
+
+        attacker.target = lure_target;
+        attacker.retarget = false;
     }
 }
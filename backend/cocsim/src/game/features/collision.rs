@@ -54,7 +54,9 @@ impl PathfindingCollisionGrid {
     }
 }
 
-pub fn check_need_redraw_collision(game: &mut Game) {
+/// Buildings with colliders which are destroyed this tick free their tiles, so
+/// collision is redrawn and paths are found again.
+pub fn check_collision_changed(game: &mut Game) {
     if game
         .cache
         .get_mut_or_default::<PreparedQuery<With<(&Building, &PathfindingCollider), &ToBeDespawned>>>(
@@ -64,6 +66,7 @@ pub fn check_need_redraw_collision(game: &mut Game) {
         .count()
         != 0
     {
+        game.collision_version += 1;
         game.need_redraw_collision = true;
     }
 }
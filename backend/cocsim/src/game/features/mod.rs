@@ -9,6 +9,7 @@ pub mod drawable;
 pub mod health;
 pub mod map_size;
 pub mod mover;
+pub mod pathfinding;
 pub mod position;
 pub mod projectiles;
 pub mod speed;
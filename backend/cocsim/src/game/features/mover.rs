@@ -18,6 +18,9 @@ use crate::{
 pub struct Mover {
     pub target: Vector2<f32>,
     pub arrived: bool,
+    /// Points to visit before [`Self::target`], next one is last. Filled by
+    /// [`crate::game::features::pathfinding`].
+    pub waypoints: Vec<Vector2<f32>>,
 }
 
 pub fn r#move(game: &mut Game) {
@@ -29,9 +32,19 @@ pub fn r#move(game: &mut Game) {
         mover.arrived = position.0.metric_distance(&mover.target) <= UNIT_DISTANCE_TO_WAYPOINT_EPS;
 
         if !mover.arrived {
-            let direction = (mover.target - position.0).normalize();
+            let step = speed.real * game.delta_time;
 
-            position.0 += direction * speed.real * game.delta_time;
+            if let Some(waypoint) = mover.waypoints.last().copied() {
+                if position.0.metric_distance(&waypoint) <= step {
+                    position.0 = waypoint;
+
+                    mover.waypoints.pop();
+                } else {
+                    position.0 += (waypoint - position.0).normalize() * step;
+                }
+            } else {
+                position.0 += (mover.target - position.0).normalize() * step;
+            }
         }
     }
 }
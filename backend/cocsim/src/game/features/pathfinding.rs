@@ -0,0 +1,211 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+};
+
+use hecs::{
+    Entity,
+    PreparedQuery,
+    World,
+};
+use nalgebra::{
+    DMatrix,
+    Vector2,
+};
+
+use crate::{
+    Game,
+    consts::*,
+    game::features::{
+        collision::PathfindingCollisionGrid,
+        mover::Mover,
+        position::Position,
+    },
+    utils::AnyMapExt,
+};
+
+const STRAIGHT_STEP_COST: u32 = 10;
+const DIAGONAL_STEP_COST: u32 = 14;
+
+/// Ground units with this component walk to [`Mover::target`] around
+/// buildings. When there is no path (target is surrounded with walls), unit
+/// walks straight. Path is found again when target or collision changes.
+#[derive(Default)]
+pub struct Pathfinder {
+    /// Collision tile of [`Mover::target`] for which [`Mover::waypoints`] were
+    /// found.
+    goal: Option<Vector2<usize>>,
+    /// [`Game::collision_version`] [`Mover::waypoints`] were found with.
+    collision_version: usize,
+}
+
+pub fn update(game: &mut Game) {
+    let mut need_path = Vec::new();
+    let collision_version = game.collision_version;
+
+    for (id, (pathfinder, mover, position)) in game
+        .cache
+        .get_mut_or_default::<PreparedQuery<(&mut Pathfinder, &mut Mover, &Position)>>()
+        .query_mut(&mut game.world)
+    {
+        let goal = collision_tile(
+            game.map_size.total_size() as usize * COLLISION_TILES_PER_MAP_TILE,
+            mover.target,
+        );
+
+        if pathfinder.goal != Some(goal) || pathfinder.collision_version != collision_version {
+            pathfinder.goal = Some(goal);
+            pathfinder.collision_version = collision_version;
+
+            need_path.push((id, position.0, mover.target));
+        }
+    }
+
+    if need_path.is_empty() {
+        return;
+    }
+
+    let collision_grid = game
+        .collision_grid
+        .get_or_insert_with(|| PathfindingCollisionGrid::new(&game.map_size, &game.world));
+
+    for (id, start, target) in need_path {
+        let waypoints = find_path(&game.world, &collision_grid.0, start, target);
+
+        game.world.get::<&mut Mover>(id).unwrap().waypoints = waypoints;
+    }
+}
+
+fn collision_tile(grid_size: usize, point: Vector2<f32>) -> Vector2<usize> {
+    let max = (grid_size - 1) as f32;
+
+    (point / COLLISION_TILE_SIZE)
+        .map(|x| x.clamp(0.0, max))
+        .map(|x| x as usize)
+}
+
+fn collision_tile_center(tile: Vector2<usize>) -> Vector2<f32> {
+    (tile.cast() + Vector2::from_element(0.5)) * COLLISION_TILE_SIZE
+}
+
+/// A* on collision grid. Tiles of buildings `start` and `target` are inside of
+/// are passable. Returns waypoints in reversed order (next waypoint is last),
+/// without `target` itself. Returns empty [`Vec`] if there is no path.
+fn find_path(
+    world: &World,
+    grid: &DMatrix<Entity>,
+    start: Vector2<f32>,
+    target: Vector2<f32>,
+) -> Vec<Vector2<f32>> {
+    let grid_size = grid.nrows();
+    let start_tile = collision_tile(grid_size, start);
+    let goal_tile = collision_tile(grid_size, target);
+
+    if start_tile == goal_tile {
+        return Vec::new();
+    }
+
+    let ignored = [
+        grid[(start_tile.x, start_tile.y)],
+        grid[(goal_tile.x, goal_tile.y)],
+    ];
+    let is_free = |x: usize, y: usize| {
+        let building = grid[(x, y)];
+
+        ignored.contains(&building) || !world.contains(building)
+    };
+    let heuristic = |tile: Vector2<usize>| {
+        let dx = tile.x.abs_diff(goal_tile.x) as u32;
+        let dy = tile.y.abs_diff(goal_tile.y) as u32;
+
+        STRAIGHT_STEP_COST * dx.max(dy) + (DIAGONAL_STEP_COST - STRAIGHT_STEP_COST) * dx.min(dy)
+    };
+
+    let mut cost = DMatrix::from_element(grid_size, grid_size, u32::MAX);
+    let mut came_from = DMatrix::from_element(grid_size, grid_size, None);
+    let mut open = BinaryHeap::new();
+
+    cost[(start_tile.x, start_tile.y)] = 0;
+    open.push(Reverse((heuristic(start_tile), start_tile.x, start_tile.y)));
+
+    while let Some(Reverse((_, x, y))) = open.pop() {
+        if (x, y) == (goal_tile.x, goal_tile.y) {
+            break;
+        }
+
+        for dx in -1i32..=1 {
+            for dy in -1i32..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+
+                let neighbor_x = x as i32 + dx;
+                let neighbor_y = y as i32 + dy;
+
+                if neighbor_x < 0
+                    || neighbor_y < 0
+                    || neighbor_x >= grid_size as i32
+                    || neighbor_y >= grid_size as i32
+                {
+                    continue;
+                }
+
+                let neighbor_x = neighbor_x as usize;
+                let neighbor_y = neighbor_y as usize;
+
+                if !is_free(neighbor_x, neighbor_y) {
+                    continue;
+                }
+
+                let diagonal = dx != 0 && dy != 0;
+
+                // don't cut corners of buildings
+                if diagonal && !(is_free(neighbor_x, y) && is_free(x, neighbor_y)) {
+                    continue;
+                }
+
+                let neighbor_cost = cost[(x, y)]
+                    + if diagonal {
+                        DIAGONAL_STEP_COST
+                    } else {
+                        STRAIGHT_STEP_COST
+                    };
+
+                if neighbor_cost < cost[(neighbor_x, neighbor_y)] {
+                    cost[(neighbor_x, neighbor_y)] = neighbor_cost;
+                    came_from[(neighbor_x, neighbor_y)] = Some((x, y));
+                    open.push(Reverse((
+                        neighbor_cost + heuristic(Vector2::new(neighbor_x, neighbor_y)),
+                        neighbor_x,
+                        neighbor_y,
+                    )));
+                }
+            }
+        }
+    }
+
+    if came_from[(goal_tile.x, goal_tile.y)].is_none() {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut previous_direction = None;
+    let mut tile = (goal_tile.x, goal_tile.y);
+
+    // keep only tiles where path changes direction
+    while let Some(previous_tile) = came_from[tile] {
+        let direction = (
+            tile.0 as i32 - previous_tile.0 as i32,
+            tile.1 as i32 - previous_tile.1 as i32,
+        );
+
+        if previous_direction.is_some_and(|previous_direction| previous_direction != direction) {
+            result.push(collision_tile_center(Vector2::new(tile.0, tile.1)));
+        }
+
+        previous_direction = Some(direction);
+        tile = previous_tile;
+    }
+
+    result
+}
@@ -12,11 +12,11 @@ use nalgebra::{
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
+    GameConfig,
     Shape,
     consts::{
         COLLISION_TILE_COLOR,
         COLLISION_TILE_SIZE,
-        MAX_ATTACK_DURATION,
         RNG_INITIAL_STATE,
     },
     game::features::{
@@ -45,11 +45,14 @@ pub struct Game {
     pub(crate) world: World,
     pub(crate) cache: AnyMap,
 
+    pub(crate) config: GameConfig,
     pub(crate) map_size: MapSize,
     pub(crate) rng: Pcg64Mcg,
     pub(crate) buildings_grid: BuildingsGrid,
     pub(crate) drop_zone: DMatrix<bool>,
     pub(crate) collision_grid: Option<PathfindingCollisionGrid>,
+    /// Incremented when passable tiles of [`Self::collision_grid`] change.
+    pub(crate) collision_version: usize,
 
     pub(crate) time_elapsed: f32,
     pub(crate) delta_time: f32,
@@ -65,7 +68,11 @@ impl Game {
     }
 
     pub fn time_left(&self) -> f32 {
-        MAX_ATTACK_DURATION - self.time_elapsed
+        self.config.max_attack_duration - self.time_elapsed
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
     }
 
     pub fn map_size(&self) -> &MapSize {
@@ -77,7 +84,7 @@ impl Game {
     }
 
     pub fn done(&mut self) -> bool {
-        self.time_elapsed == MAX_ATTACK_DURATION || self.stars() == 3
+        self.time_elapsed == self.config.max_attack_duration || self.stars() == 3
     }
 
     pub fn percentage_destroyed(&mut self) -> f32 {
@@ -144,16 +151,25 @@ impl Game {
         self.need_redraw_collision
     }
 
-    pub fn new(map: &ValidatedMap, enable_collision_grid: bool, rng: Option<Pcg64Mcg>) -> Self {
+    pub fn new(
+        map: &ValidatedMap,
+        config: &GameConfig,
+        enable_collision_grid: bool,
+        rng: Option<Pcg64Mcg>,
+    ) -> Self {
         let mut world = World::new();
         let mut cache = AnyMap::new();
 
-        let rng = rng.unwrap_or(Pcg64Mcg::new(RNG_INITIAL_STATE));
+        let mut rng = rng.unwrap_or(Pcg64Mcg::new(RNG_INITIAL_STATE));
 
         for building in &map.buildings {
             building.spawn(&mut world);
         }
 
+        for building in map.sample_trap_zones(&mut rng) {
+            building.spawn(&mut world);
+        }
+
         let initial_counted_buildings_count = Self::counted_buildings_count(&mut cache, &mut world);
 
         let buildings_grid = BuildingsGrid::new(&map.size(), &mut world);
@@ -164,11 +180,13 @@ impl Game {
             world,
             cache,
 
+            config: config.clone(),
             map_size: map.size(),
             rng,
             buildings_grid,
             drop_zone: map.drop_zone().to_owned(),
             collision_grid,
+            collision_version: 0,
 
             time_elapsed: 0.0,
             delta_time: 0.0,
@@ -204,6 +222,7 @@ impl Game {
         features::stunned::clear(self);
         features::projectiles::air_sweeper_projectile::update(self);
 
+        features::pathfinding::update(self);
         features::mover::r#move(self);
 
         features::health::handle_splash_damage_events(self);
@@ -213,12 +232,15 @@ impl Game {
         features::delay::update(self);
 
         if self.collision_grid.is_some() {
-            features::collision::check_need_redraw_collision(self);
+            features::collision::check_collision_changed(self);
         }
 
         features::to_be_despawned::handle_to_be_despawned(self);
 
-        self.time_elapsed = MAX_ATTACK_DURATION.min(self.time_elapsed + self.delta_time);
+        self.time_elapsed = self
+            .config
+            .max_attack_duration
+            .min(self.time_elapsed + self.delta_time);
     }
 
     pub fn draw_entities(&mut self) -> Vec<Shape> {
@@ -276,6 +298,7 @@ impl Game {
     pub(crate) fn update_collision_grid(&mut self) {
         if self.collision_grid.is_some() {
             self.collision_grid = Some(PathfindingCollisionGrid::new(&self.map_size, &self.world));
+            self.collision_version += 1;
 
             self.need_redraw_collision = true;
         }
@@ -0,0 +1,51 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    attack_optimizer::AttackObjective,
+    consts::{
+        MAX_ATTACK_DURATION,
+        MAX_UNIT_DROP_TIME,
+        SPELL_DROP_COOLDOWN,
+        SPELL_GROUP_DROP_COOLDOWN,
+        UNIT_DROP_COOLDOWN,
+        UNIT_GROUP_DROP_COOLDOWN,
+    },
+};
+
+/// Attack timings. [`Default`] matches regular multiplayer attacks.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameConfig {
+    pub max_attack_duration: f32,
+    /// Latest drop time attack optimizers can choose for unit or spell
+    /// group.
+    pub max_unit_drop_time: f32,
+    /// Time between unit drops when deploying multiple units of same type
+    /// (unit group).
+    pub unit_drop_cooldown: f32,
+    /// Cooldown after switching active unit group.
+    pub unit_group_drop_cooldown: f32,
+    /// Cooldown after dropping the spell.
+    pub spell_drop_cooldown: f32,
+    /// Cooldown after switching active spell group.
+    pub spell_group_drop_cooldown: f32,
+    /// Score attack optimizers maximize.
+    pub objective: AttackObjective,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            max_attack_duration: MAX_ATTACK_DURATION,
+            max_unit_drop_time: MAX_UNIT_DROP_TIME,
+            unit_drop_cooldown: UNIT_DROP_COOLDOWN,
+            unit_group_drop_cooldown: UNIT_GROUP_DROP_COOLDOWN,
+            spell_drop_cooldown: SPELL_DROP_COOLDOWN,
+            spell_group_drop_cooldown: SPELL_GROUP_DROP_COOLDOWN,
+            objective: AttackObjective::default(),
+        }
+    }
+}
@@ -2,23 +2,38 @@ pub mod attack_optimizer;
 pub mod buildings;
 pub mod colliders;
 pub mod consts;
+pub mod defense_optimizer;
+mod drop_zone;
 mod game;
+mod game_config;
 mod geometry;
 mod map;
 mod shape;
 pub mod spells;
 #[cfg(feature = "test-maps")]
 pub mod test_maps;
+mod trap_zone;
 pub mod units;
 mod usize_with_max;
 pub mod utils;
+pub mod war_assignment;
 mod with_housing_space;
 
+pub use drop_zone::{
+    DropZonePolygon,
+    DropZoneRules,
+    DropZoneRulesPreset,
+};
 pub use game::Game;
+pub use game_config::GameConfig;
 pub use map::*;
 pub use shape::{
     Shape,
     ShapeColor,
 };
+pub use trap_zone::{
+    TrapZone,
+    TrapZoneTile,
+};
 pub use usize_with_max::*;
 pub use with_housing_space::*;
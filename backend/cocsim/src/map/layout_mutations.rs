@@ -0,0 +1,243 @@
+use anyhow::{
+    bail,
+    ensure,
+};
+use nalgebra::{
+    DMatrix,
+    Scalar,
+    Vector2,
+};
+
+use crate::{
+    UsizeWithMax,
+    ValidatedMap,
+    buildings::{
+        BuildingModel,
+        BuildingModelEnum,
+    },
+};
+
+/// Layout mutations used by base search. Unlike building [`ValidatedMap`]
+/// from scratch, they update occupancy and drop zone only around moved
+/// buildings, so they are cheap enough to be applied millions of times.
+/// Drop zone rules of the map are kept. Maps with variants (see
+/// [`ValidatedMap::from_variants`]) can't be mutated.
+impl ValidatedMap {
+    /// Moves each building `i` of `moves` so its top left tile is at
+    /// `position`. Buildings are moved at once, so they can take places of
+    /// each other. Map is left unchanged if any building can't be placed.
+    pub fn move_buildings(&mut self, moves: &[(usize, Vector2<usize>)]) -> anyhow::Result<()> {
+        ensure!(
+            self.variants.is_empty(),
+            "Map with variants can't be mutated"
+        );
+
+        let mut positions = Vec::with_capacity(moves.len());
+
+        for (k, &(i, position)) in moves.iter().enumerate() {
+            ensure!(i < self.buildings.len(), "Building index is out of range");
+            ensure!(
+                moves[..k].iter().all(|(other, _)| *other != i),
+                "Building is moved twice"
+            );
+
+            positions.push((
+                UsizeWithMax::new(position.x)?,
+                UsizeWithMax::new(position.y)?,
+            ));
+        }
+
+        let old_positions = moves
+            .iter()
+            .map(|&(i, _)| (self.buildings[i].x, self.buildings[i].y))
+            .collect::<Vec<_>>();
+
+        for &(i, _) in moves {
+            self.place_building(i, false);
+        }
+
+        for (k, (&(i, _), &(x, y))) in moves.iter().zip(&positions).enumerate() {
+            if let Err(err) = self.check_building_position(&self.buildings[i].model, *x, *y) {
+                for &(i, _) in &moves[..k] {
+                    self.place_building(i, false);
+                }
+
+                for (&(i, _), &(x, y)) in moves.iter().zip(&old_positions) {
+                    self.map.buildings[i].x = x;
+                    self.map.buildings[i].y = y;
+                    self.place_building(i, true);
+                }
+
+                return Err(err);
+            }
+
+            self.map.buildings[i].x = x;
+            self.map.buildings[i].y = y;
+            self.place_building(i, true);
+        }
+
+        Ok(())
+    }
+
+    /// Moves building `i` so its top left tile is at `position`.
+    pub fn move_building(&mut self, i: usize, position: Vector2<usize>) -> anyhow::Result<()> {
+        self.move_buildings(&[(i, position)])
+    }
+
+    /// Swaps positions of buildings `i` and `j`, which should have the same
+    /// size.
+    pub fn swap_buildings(&mut self, i: usize, j: usize) -> anyhow::Result<()> {
+        ensure!(
+            i < self.buildings.len() && j < self.buildings.len(),
+            "Building index is out of range"
+        );
+        ensure!(i != j, "Building can't be swapped with itself");
+        ensure!(
+            self.buildings[i].model.r#type().size == self.buildings[j].model.r#type().size,
+            "Swapped buildings should have the same size"
+        );
+
+        let position_i = Vector2::new(*self.buildings[i].x, *self.buildings[i].y);
+        let position_j = Vector2::new(*self.buildings[j].x, *self.buildings[j].y);
+
+        self.move_buildings(&[(i, position_j), (j, position_i)])
+    }
+
+    /// Wall `i` and walls continuing it in a straight line along x axis (or y
+    /// axis if `vertical` is `true`), ordered by position.
+    pub fn wall_segment(&self, i: usize, vertical: bool) -> anyhow::Result<Vec<usize>> {
+        ensure!(i < self.buildings.len(), "Building index is out of range");
+        ensure!(self.is_wall(i), "Building is not a wall");
+
+        let key = |j: usize| {
+            let building = &self.buildings[j];
+
+            if vertical {
+                (*building.x, *building.y)
+            } else {
+                (*building.y, *building.x)
+            }
+        };
+        let (line, start) = key(i);
+        let mut walls = (0..self.buildings.len())
+            .filter(|&j| self.is_wall(j) && key(j).0 == line)
+            .collect::<Vec<_>>();
+
+        walls.sort_by_key(|&j| key(j).1);
+
+        let k = walls
+            .iter()
+            .position(|&j| j == i)
+            .expect("Wall is in its line");
+        let first = (0..k)
+            .rev()
+            .take_while(|&l| key(walls[l]).1 + (k - l) == start)
+            .last()
+            .unwrap_or(k);
+        let last = (k + 1..walls.len())
+            .take_while(|&l| key(walls[l]).1 == start + (l - k))
+            .last()
+            .unwrap_or(k);
+
+        Ok(walls[first..=last].to_vec())
+    }
+
+    /// Moves `walls` (e.g. [`Self::wall_segment`]) by `offset` tiles.
+    pub fn move_walls(&mut self, walls: &[usize], offset: Vector2<isize>) -> anyhow::Result<()> {
+        let mut moves = Vec::with_capacity(walls.len());
+
+        for &i in walls {
+            ensure!(i < self.buildings.len(), "Building index is out of range");
+            ensure!(self.is_wall(i), "Building is not a wall");
+
+            let building = &self.buildings[i];
+            let (Some(x), Some(y)) = (
+                building.x.checked_add_signed(offset.x),
+                building.y.checked_add_signed(offset.y),
+            ) else {
+                bail!("Building is outside of base");
+            };
+
+            moves.push((i, Vector2::new(x, y)));
+        }
+
+        self.move_buildings(&moves)
+    }
+
+    /// Layout rotated by a quarter turn around center of the map. Building at
+    /// (`x`, `y`) is moved to (`total_size - y - size`, `x`).
+    pub fn rotated(&self) -> anyhow::Result<Self> {
+        let total_size = self.drop_zone.nrows();
+
+        self.transformed(|x, y, size| (total_size - y - size, x))
+    }
+
+    /// Layout mirrored along x axis. Building at (`x`, `y`) is moved to
+    /// (`total_size - x - size`, `y`).
+    pub fn mirrored(&self) -> anyhow::Result<Self> {
+        let total_size = self.drop_zone.nrows();
+
+        self.transformed(|x, y, size| (total_size - x - size, y))
+    }
+
+    fn is_wall(&self, i: usize) -> bool {
+        matches!(self.buildings[i].model, BuildingModelEnum::WallModel(_))
+    }
+
+    /// `transform` maps top left tile and size of square object to its new top
+    /// left tile. It should be a symmetry of the map, so buildings, trap zones
+    /// and drop zone are just moved and don't need to be validated again.
+    fn transformed(
+        &self,
+        transform: impl Fn(usize, usize, usize) -> (usize, usize),
+    ) -> anyhow::Result<Self> {
+        ensure!(
+            self.variants.is_empty(),
+            "Map with variants can't be mutated"
+        );
+
+        let mut result = self.clone();
+
+        for building in &mut result.map.buildings {
+            let (x, y) = transform(*building.x, *building.y, building.model.r#type().size.x);
+
+            building.x = UsizeWithMax::new(x)?;
+            building.y = UsizeWithMax::new(y)?;
+        }
+
+        for trap_zone in &mut result.map.trap_zones {
+            let size = trap_zone.trap.r#type().size.x;
+
+            for tile in &mut trap_zone.tiles {
+                let (x, y) = transform(*tile.x, *tile.y, size);
+
+                tile.x = UsizeWithMax::new(x)?;
+                tile.y = UsizeWithMax::new(y)?;
+            }
+        }
+
+        result.drop_zone = transform_grid(&self.drop_zone, &transform);
+        result.blocked_count = transform_grid(&self.blocked_count, &transform);
+        result.buildings_grid = transform_grid(&self.buildings_grid, &transform);
+        result.trap_zones_grid = transform_grid(&self.trap_zones_grid, &transform);
+        result.drop_zone_free_tiles = Default::default();
+        result.drop_zone_polygons = Default::default();
+
+        Ok(result)
+    }
+}
+
+fn transform_grid<T: Scalar + Copy>(
+    grid: &DMatrix<T>,
+    transform: impl Fn(usize, usize, usize) -> (usize, usize),
+) -> DMatrix<T> {
+    let mut result = grid.clone();
+
+    for x in 0..grid.nrows() {
+        for y in 0..grid.ncols() {
+            result[transform(x, y, 1)] = grid[(x, y)];
+        }
+    }
+
+    result
+}
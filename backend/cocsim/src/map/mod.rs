@@ -0,0 +1,366 @@
+mod layout_mutations;
+
+use std::{
+    ops::Deref,
+    sync::OnceLock,
+};
+
+use anyhow::ensure;
+use arbitrary::Arbitrary;
+use nalgebra::{
+    DMatrix,
+    Vector2,
+};
+use rand::Rng;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    buildings::{
+        Building,
+        BuildingModel,
+        BuildingModelEnum,
+    },
+    consts::{
+        MAX_BASE_SIZE,
+        MAX_BORDER_SIZE,
+        MAX_BUILDINGS_COUNT,
+        MIN_BASE_SIZE,
+        MIN_BORDER_SIZE,
+    },
+    drop_zone::{
+        DropZonePolygon,
+        DropZoneRules,
+        DropZoneRulesPreset,
+    },
+    game::features::map_size::MapSize,
+    trap_zone::TrapZone,
+};
+
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Map {
+    pub base_size: usize,
+    pub border_size: usize,
+
+    pub buildings: Vec<Building>,
+    /// Traps with unknown positions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trap_zones: Vec<TrapZone>,
+    /// Rules drop zone is computed with when map is validated by
+    /// [`ValidatedMap::try_from`].
+    #[serde(default, skip_serializing_if = "DropZoneRulesPreset::is_default")]
+    pub drop_zone_rules: DropZoneRulesPreset,
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidatedMap {
+    map: Map,
+    drop_zone_rules: DropZoneRules,
+    drop_zone: DMatrix<bool>,
+    /// Number of tiles of buildings which block each tile. Tile is in drop
+    /// zone if it's zero (or if it's border tile allowed by
+    /// [`DropZoneRules::allow_border`]).
+    blocked_count: DMatrix<u16>,
+    /// Computed on first use, reset by layout mutations.
+    drop_zone_free_tiles: OnceLock<Vec<(usize, usize)>>,
+    /// Computed on first use, reset by layout mutations.
+    drop_zone_polygons: OnceLock<Vec<DropZonePolygon>>,
+    /// `true` at tiles occupied by buildings.
+    buildings_grid: DMatrix<bool>,
+    /// `true` at tiles traps of [`Map::trap_zones`] can occupy, buildings
+    /// can't be placed there.
+    trap_zones_grid: DMatrix<bool>,
+    /// Possible versions of this map (e.g. with different trap positions) and
+    /// their weights. Empty if map is exactly known.
+    variants: Vec<(ValidatedMap, f32)>,
+}
+
+impl ValidatedMap {
+    pub fn drop_zone(&self) -> &DMatrix<bool> {
+        &self.drop_zone
+    }
+
+    pub fn size(&self) -> MapSize {
+        MapSize {
+            base_size: self.base_size as i32,
+            border_size: self.border_size as i32,
+        }
+    }
+
+    pub fn drop_zone_free_tiles(&self) -> &[(usize, usize)] {
+        self.drop_zone_free_tiles.get_or_init(|| {
+            let mut result = Vec::new();
+
+            for x in 0..self.drop_zone.nrows() {
+                for y in 0..self.drop_zone.ncols() {
+                    if self.drop_zone[(x, y)] {
+                        result.push((x, y));
+                    }
+                }
+            }
+
+            result
+        })
+    }
+
+    /// Edges of [`Self::drop_zone`] around blocked areas.
+    pub fn drop_zone_polygons(&self) -> &[DropZonePolygon] {
+        self.drop_zone_polygons
+            .get_or_init(|| DropZonePolygon::trace(&self.drop_zone))
+    }
+
+    /// Rules drop zone was computed with.
+    pub fn drop_zone_rules(&self) -> &DropZoneRules {
+        &self.drop_zone_rules
+    }
+
+    /// Returns `position` if it is inside of [`Self::drop_zone`], otherwise
+    /// nearest point inside of it. [`None`] if drop zone is empty.
+    pub fn nearest_drop_zone_point(&self, position: Vector2<f32>) -> Option<Vector2<f32>> {
+        // keep point off the tile edge, so it doesn't land on blocked neighbor
+        const EDGE_OFFSET: f32 = 0.01;
+
+        let total_size = self.size().total_size() as f32;
+        let inside_map = position.iter().all(|x| (0.0..total_size).contains(x));
+
+        if inside_map && self.drop_zone[(position.x as usize, position.y as usize)] {
+            return Some(position);
+        }
+
+        self.drop_zone_free_tiles()
+            .iter()
+            .map(|(x, y)| {
+                let min = Vector2::new(*x as f32, *y as f32);
+
+                position.zip_zip_map(
+                    &(min.add_scalar(EDGE_OFFSET)),
+                    &(min.add_scalar(1.0 - EDGE_OFFSET)),
+                    |x, min, max| x.clamp(min, max),
+                )
+            })
+            .min_by(|a, b| {
+                a.metric_distance(&position)
+                    .total_cmp(&b.metric_distance(&position))
+            })
+    }
+
+    pub fn new(value: Map, drop_zone_rules: &DropZoneRules) -> anyhow::Result<Self> {
+        ensure!(value.base_size >= MIN_BASE_SIZE && value.base_size <= MAX_BASE_SIZE);
+        ensure!(value.border_size >= MIN_BORDER_SIZE && value.border_size <= MAX_BORDER_SIZE);
+        ensure!(value.buildings.len() <= MAX_BUILDINGS_COUNT);
+
+        let total_size = value.base_size + value.border_size * 2;
+        let mut result = Self {
+            drop_zone_rules: drop_zone_rules.clone(),
+            drop_zone: DMatrix::from_element(total_size, total_size, true),
+            blocked_count: DMatrix::from_element(total_size, total_size, 0),
+            drop_zone_free_tiles: OnceLock::new(),
+            drop_zone_polygons: OnceLock::new(),
+            buildings_grid: DMatrix::from_element(total_size, total_size, false),
+            trap_zones_grid: DMatrix::from_element(total_size, total_size, false),
+            variants: Vec::new(),
+            map: value,
+        };
+        let mut has_town_hall = false;
+
+        for i in 0..result.buildings.len() {
+            let building = &result.buildings[i];
+
+            if let BuildingModelEnum::TownHallModel(_) = building.model {
+                ensure!(!has_town_hall);
+
+                has_town_hall = true;
+            }
+
+            result.check_building_position(&building.model, *building.x, *building.y)?;
+            result.place_building(i, true);
+        }
+
+        ensure!(has_town_hall);
+
+        let map_size = result.size();
+
+        for trap_zone in &result.map.trap_zones {
+            trap_zone.validate(&map_size, &result.buildings_grid)?;
+
+            let size = trap_zone.trap.r#type().size;
+
+            for tile in &trap_zone.tiles {
+                for x in *tile.x..*tile.x + size.x {
+                    for y in *tile.y..*tile.y + size.y {
+                        result.trap_zones_grid[(x, y)] = true;
+                    }
+                }
+            }
+        }
+
+        if drop_zone_rules.allow_border {
+            for x in 0..total_size {
+                for y in 0..total_size {
+                    result.update_drop_zone_tile(x, y);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Fails if building of `model` can't be placed at (`x`, `y`): it
+    /// doesn't fit into base or overlaps other building or trap zone.
+    fn check_building_position(
+        &self,
+        model: &BuildingModelEnum,
+        x: usize,
+        y: usize,
+    ) -> anyhow::Result<()> {
+        let base_start = self.border_size;
+        let base_end = self.base_size + self.border_size;
+        let size = model.r#type().size;
+
+        ensure!(
+            x >= base_start && x + size.x <= base_end && y >= base_start && y + size.y <= base_end,
+            "Building is outside of base"
+        );
+
+        for tile_x in x..x + size.x {
+            for tile_y in y..y + size.y {
+                ensure!(
+                    !self.buildings_grid[(tile_x, tile_y)],
+                    "Building overlaps another building"
+                );
+                ensure!(
+                    !self.trap_zones_grid[(tile_x, tile_y)],
+                    "Building overlaps trap zone"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks tiles of building `i` as occupied (or free if `place` is
+    /// `false`) and updates drop zone around them. Drop zone of each building
+    /// tile is updated only within its margin, unlike recomputing the whole
+    /// [`Self::drop_zone`].
+    fn place_building(&mut self, i: usize, place: bool) {
+        let building = &self.map.buildings[i];
+        let building_type = building.model.r#type();
+        let (start_x, start_y) = (*building.x, *building.y);
+        let margin = self.drop_zone_rules.margin(&building.model);
+        let total_size = self.drop_zone.nrows();
+
+        for x in start_x..start_x + building_type.size.x {
+            for y in start_y..start_y + building_type.size.y {
+                self.buildings_grid[(x, y)] = place;
+
+                let Some(margin) = margin else {
+                    continue;
+                };
+
+                for blocked_x in x.saturating_sub(margin)..=(x + margin).min(total_size - 1) {
+                    for blocked_y in y.saturating_sub(margin)..=(y + margin).min(total_size - 1) {
+                        let count = &mut self.blocked_count[(blocked_x, blocked_y)];
+
+                        if place {
+                            *count += 1;
+                        } else {
+                            *count -= 1;
+                        }
+
+                        self.update_drop_zone_tile(blocked_x, blocked_y);
+                    }
+                }
+            }
+        }
+
+        self.drop_zone_free_tiles = OnceLock::new();
+        self.drop_zone_polygons = OnceLock::new();
+    }
+
+    fn update_drop_zone_tile(&mut self, x: usize, y: usize) {
+        self.drop_zone[(x, y)] = self.blocked_count[(x, y)] == 0
+            || self.drop_zone_rules.allow_border
+                && self.size().is_border(Vector2::new(x as i32, y as i32));
+    }
+
+    /// Map which is one of weighted `variants` (e.g. scouted base with traps
+    /// at several plausible positions). Plan is executed with each seed on
+    /// every variant and stats are weighted averages. The first variant is
+    /// used for everything else, e.g. plan encoding and rendering, so
+    /// variants must have the same size.
+    pub fn from_variants(variants: Vec<(ValidatedMap, f32)>) -> anyhow::Result<Self> {
+        ensure!(!variants.is_empty(), "At least one map variant expected");
+
+        let mut result = variants[0].0.clone();
+
+        for (variant, weight) in &variants {
+            ensure!(
+                *weight > 0.0 && weight.is_finite(),
+                "Map variant weight should be positive"
+            );
+            ensure!(
+                variant.base_size == result.base_size && variant.border_size == result.border_size,
+                "Map variants should have the same size"
+            );
+            ensure!(
+                variant.variants.is_empty(),
+                "Map variant can't have variants"
+            );
+        }
+
+        result.variants = variants;
+
+        Ok(result)
+    }
+
+    /// Buildings placed by [`Map::trap_zones`]. Zones are sampled in order,
+    /// traps of the same zone and of different zones don't overlap.
+    pub fn sample_trap_zones(&self, rng: &mut impl Rng) -> Vec<Building> {
+        let mut occupied = self.buildings_grid.clone();
+
+        self.trap_zones
+            .iter()
+            .flat_map(|trap_zone| trap_zone.sample(&mut occupied, rng))
+            .collect()
+    }
+
+    /// 1 if map has no variants.
+    pub fn variants_count(&self) -> usize {
+        self.variants.len().max(1)
+    }
+
+    /// Variants and their weights, the map itself if it has no variants.
+    pub fn variants(&self) -> impl Iterator<Item = (&ValidatedMap, f32)> {
+        self.variants
+            .is_empty()
+            .then_some((self, 1.0))
+            .into_iter()
+            .chain(
+                self.variants
+                    .iter()
+                    .map(|(variant, weight)| (variant, *weight)),
+            )
+    }
+}
+
+impl TryFrom<Map> for ValidatedMap {
+    type Error = anyhow::Error;
+
+    /// Uses [`Map::drop_zone_rules`].
+    fn try_from(value: Map) -> anyhow::Result<Self> {
+        let drop_zone_rules = value.drop_zone_rules.rules();
+
+        Self::new(value, drop_zone_rules)
+    }
+}
+
+impl Deref for ValidatedMap {
+    type Target = Map;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
@@ -36,6 +36,7 @@ pub fn spawn_spell(
         Mover {
             target: position,
             arrived: false,
+            waypoints: Vec::new(),
         },
         Delay {
             time_left: SPELL_DROP_DELAY,
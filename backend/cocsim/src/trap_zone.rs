@@ -0,0 +1,133 @@
+use anyhow::ensure;
+use arbitrary::Arbitrary;
+use nalgebra::DMatrix;
+use rand::Rng;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    UsizeWithMax,
+    buildings::{
+        Building,
+        BuildingModel,
+        BuildingModelEnum,
+    },
+    consts::MAX_BUILDING_POS,
+    game::features::map_size::MapSize,
+};
+
+/// Candidate tile of [`TrapZone`], position of trap's top left corner.
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+pub struct TrapZoneTile {
+    pub x: UsizeWithMax<MAX_BUILDING_POS>,
+    pub y: UsizeWithMax<MAX_BUILDING_POS>,
+    /// Relative probability of trap being here.
+    pub weight: f32,
+}
+
+/// Traps of scouted base with unknown positions. Concrete positions are
+/// sampled by [`crate::Game::new`], so executions with different seeds see
+/// different trap layouts.
+#[derive(Serialize, Deserialize, Debug, Arbitrary, Clone)]
+pub struct TrapZone {
+    pub trap: BuildingModelEnum,
+    pub count: usize,
+    pub tiles: Vec<TrapZoneTile>,
+}
+
+impl TrapZone {
+    /// `buildings_grid` has `true` at tiles occupied by buildings.
+    pub(crate) fn validate(
+        &self,
+        map_size: &MapSize,
+        buildings_grid: &DMatrix<bool>,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            !self.trap.r#type().affects_drop_zone,
+            "Trap zone building should be a trap"
+        );
+        ensure!(
+            self.count <= self.tiles.len(),
+            "Trap zone has less tiles than traps"
+        );
+
+        let size = self.trap.r#type().size;
+        let base_start = map_size.border_size as usize;
+        let base_end = (map_size.base_size + map_size.border_size) as usize;
+
+        for tile in &self.tiles {
+            ensure!(
+                tile.weight >= 0.0 && tile.weight.is_finite(),
+                "Trap zone tile weight should be non-negative"
+            );
+            ensure!(
+                *tile.x >= base_start
+                    && *tile.x + size.x <= base_end
+                    && *tile.y >= base_start
+                    && *tile.y + size.y <= base_end,
+                "Trap zone tile is outside of base"
+            );
+
+            for x in *tile.x..*tile.x + size.x {
+                for y in *tile.y..*tile.y + size.y {
+                    ensure!(!buildings_grid[(x, y)], "Trap zone tile is occupied");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Places traps at random tiles with probability proportional to tile
+    /// weight. Tiles overlapping traps placed before are skipped, so less
+    /// than [`Self::count`] traps are placed if zone runs out of free tiles.
+    /// Placed traps are marked in `occupied`.
+    pub(crate) fn sample(&self, occupied: &mut DMatrix<bool>, rng: &mut impl Rng) -> Vec<Building> {
+        let size = self.trap.r#type().size;
+        let mut result = Vec::with_capacity(self.count);
+
+        for _ in 0..self.count {
+            let free = self
+                .tiles
+                .iter()
+                .filter(|tile| {
+                    tile.weight > 0.0
+                        && (*tile.x..*tile.x + size.x)
+                            .all(|x| (*tile.y..*tile.y + size.y).all(|y| !occupied[(x, y)]))
+                })
+                .collect::<Vec<_>>();
+
+            if free.is_empty() {
+                break;
+            }
+
+            let weights_sum = free.iter().map(|tile| tile.weight).sum::<f32>();
+            let mut left = rng.random_range(0.0..weights_sum);
+            // last tile if rounding errors made `left` exceed weights sum
+            let tile = free
+                .iter()
+                .find(|tile| {
+                    left -= tile.weight;
+
+                    left < 0.0
+                })
+                .unwrap_or(free.last().unwrap());
+
+            for x in *tile.x..*tile.x + size.x {
+                for y in *tile.y..*tile.y + size.y {
+                    occupied[(x, y)] = true;
+                }
+            }
+
+            result.push(Building {
+                model: self.trap.clone(),
+                x: tile.x,
+                y: tile.y,
+            });
+        }
+
+        result
+    }
+}
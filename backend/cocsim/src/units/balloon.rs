@@ -1,5 +1,8 @@
 use arbitrary::Arbitrary;
-use hecs::World;
+use hecs::{
+    Entity,
+    World,
+};
 use nalgebra::Vector2;
 use serde::{
     Deserialize,
@@ -125,7 +128,7 @@ impl UnitModel for BalloonModel {
         *self.level
     }
 
-    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) {
+    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) -> Entity {
         let level = &BALLOON_LEVELS[*self.level];
 
         let id = spawn_air_unit(
@@ -167,5 +170,7 @@ impl UnitModel for BalloonModel {
                 ),
             )
             .unwrap();
+
+        id
     }
 }
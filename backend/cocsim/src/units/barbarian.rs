@@ -0,0 +1,183 @@
+use arbitrary::Arbitrary;
+use hecs::{
+    Entity,
+    World,
+};
+use nalgebra::Vector2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    Shape,
+    ShapeColor,
+    colliders::PointCollider,
+    game::features::{
+        actions::MeleeAttack,
+        attack::{
+            AttackTarget,
+            AttackTargetFlags,
+            Attacker,
+            FalseRetargetCondition,
+            Team,
+            targeting::{
+                air_unit::NearestTargetPrioritizer,
+                ground_unit::GroundUnitFindTarget,
+            },
+        },
+        damage::DamageMultiplier,
+        drawable::Drawable,
+        health::Health,
+        mover::Mover,
+        pathfinding::Pathfinder,
+        position::Position,
+        speed::Speed,
+        unit::Unit,
+    },
+    units::{
+        UnitModel,
+        UnitType,
+    },
+    usize_with_max::UsizeWithMax,
+};
+
+struct BarbarianLevel {
+    pub health: f32,
+    pub attack_damage: f32,
+}
+
+const BARBARIAN_LEVELS_LEN: usize = 12;
+const BARBARIAN_LEVEL_INDEX_MAX: usize = BARBARIAN_LEVELS_LEN - 1;
+const BARBARIAN_LEVELS: [BarbarianLevel; BARBARIAN_LEVELS_LEN] = [
+    BarbarianLevel {
+        health: 45.0,
+        attack_damage: 8.0,
+    },
+    BarbarianLevel {
+        health: 54.0,
+        attack_damage: 11.0,
+    },
+    BarbarianLevel {
+        health: 65.0,
+        attack_damage: 14.0,
+    },
+    BarbarianLevel {
+        health: 85.0,
+        attack_damage: 18.0,
+    },
+    BarbarianLevel {
+        health: 105.0,
+        attack_damage: 23.0,
+    },
+    BarbarianLevel {
+        health: 125.0,
+        attack_damage: 26.0,
+    },
+    BarbarianLevel {
+        health: 160.0,
+        attack_damage: 30.0,
+    },
+    BarbarianLevel {
+        health: 205.0,
+        attack_damage: 34.0,
+    },
+    BarbarianLevel {
+        health: 230.0,
+        attack_damage: 38.0,
+    },
+    BarbarianLevel {
+        health: 250.0,
+        attack_damage: 42.0,
+    },
+    BarbarianLevel {
+        health: 270.0,
+        attack_damage: 45.0,
+    },
+    BarbarianLevel {
+        health: 290.0,
+        attack_damage: 48.0,
+    },
+];
+
+const BARBARIAN: UnitType = UnitType {
+    name: "Barbarian",
+    housing_space: 1,
+    levels: BARBARIAN_LEVELS.len(),
+    clan_castle_deployment_priority: 0,
+    attack_air: false,
+    attack_ground: true,
+};
+
+inventory::submit! {BARBARIAN}
+
+const BARBARIAN_SPEED: f32 = 2.0;
+const BARBARIAN_ATTACK_COOLDOWN: f32 = 1.0;
+const BARBARIAN_ATTACK_RANGE: f32 = 0.4;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Arbitrary)]
+pub struct BarbarianModel {
+    pub level: UsizeWithMax<BARBARIAN_LEVEL_INDEX_MAX>,
+}
+
+impl UnitModel for BarbarianModel {
+    fn r#type(&self) -> &'static UnitType {
+        &BARBARIAN
+    }
+
+    fn level(&self) -> usize {
+        *self.level
+    }
+
+    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) -> Entity {
+        let level = &BARBARIAN_LEVELS[*self.level];
+
+        world.spawn((
+            Position(position),
+            Unit {
+                housing_space: BARBARIAN.housing_space,
+            },
+            DamageMultiplier { value: 1.0 },
+            Speed {
+                raw: BARBARIAN_SPEED,
+                real: BARBARIAN_SPEED,
+            },
+            Mover {
+                arrived: true,
+                target: position,
+                waypoints: Vec::new(),
+            },
+            Pathfinder::default(),
+            Health {
+                health: level.health,
+                max_health: level.health,
+                incoming_damage: 0.0,
+            },
+            team,
+            Attacker {
+                attack_cooldown: BARBARIAN_ATTACK_COOLDOWN,
+                remaining_attack_cooldown: BARBARIAN_ATTACK_COOLDOWN,
+                target: Entity::DANGLING,
+                retarget_condition: FalseRetargetCondition.into(),
+                retarget: true,
+                attack: Box::new(MeleeAttack {
+                    damage: level.attack_damage,
+                }),
+            },
+            AttackTarget {
+                collider: PointCollider::zero().into(),
+                flags: AttackTargetFlags::UNIT | AttackTargetFlags::GROUND,
+            },
+            GroundUnitFindTarget {
+                prioritizer: NearestTargetPrioritizer.into(),
+                attack_range: BARBARIAN_ATTACK_RANGE,
+            },
+            Drawable::Shapes(vec![Shape::Circle {
+                x: 0.0,
+                y: 0.0,
+                radius: 0.2,
+                color: ShapeColor::new(255, 200, 0),
+            }]),
+        ))
+    }
+}
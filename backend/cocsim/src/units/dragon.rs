@@ -1,5 +1,8 @@
 use arbitrary::Arbitrary;
-use hecs::World;
+use hecs::{
+    Entity,
+    World,
+};
 use nalgebra::Vector2;
 use serde::{
     Deserialize,
@@ -15,7 +18,7 @@ use crate::{
             Team,
             targeting::air_unit::{
                 AirUnitFindTarget,
-                DragonTargetPrioritizer,
+                NearestTargetPrioritizer,
             },
         },
         drawable::Drawable,
@@ -115,7 +118,7 @@ impl UnitModel for DragonModel {
         *self.level
     }
 
-    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) {
+    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) -> Entity {
         let level = &DRAGON_LEVELS[*self.level];
 
         let id = spawn_air_unit(
@@ -141,10 +144,12 @@ impl UnitModel for DragonModel {
             .insert_one(
                 id,
                 AirUnitFindTarget {
-                    prioritizer: DragonTargetPrioritizer.into(),
+                    prioritizer: NearestTargetPrioritizer.into(),
                     attack_range: DRAGON_ATTACK_RANGE,
                 },
             )
             .unwrap();
+
+        id
     }
 }
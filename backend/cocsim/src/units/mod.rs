@@ -1,4 +1,5 @@
 mod balloon;
+mod barbarian;
 mod dragon;
 pub mod utils;
 
@@ -6,9 +7,13 @@ use std::cmp::Ordering;
 
 use arbitrary::Arbitrary;
 pub use balloon::*;
+pub use barbarian::*;
 pub use dragon::*;
 use enum_dispatch::enum_dispatch;
-use hecs::World;
+use hecs::{
+    Entity,
+    World,
+};
 use nalgebra::Vector2;
 use serde::{
     Deserialize,
@@ -40,7 +45,8 @@ pub trait UnitModel {
 
     fn level(&self) -> usize;
 
-    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team);
+    /// Returns spawned unit id.
+    fn spawn(&self, world: &mut World, position: Vector2<f32>, team: Team) -> Entity;
 }
 
 #[enum_dispatch(UnitModel)]
@@ -49,6 +55,8 @@ pub trait UnitModel {
 pub enum UnitModelEnum {
     #[serde(rename = "Balloon")]
     BalloonModel,
+    #[serde(rename = "Barbarian")]
+    BarbarianModel,
     #[serde(rename = "Dragon")]
     DragonModel,
 }
@@ -47,6 +47,7 @@ pub fn spawn_air_unit(
         Mover {
             arrived: true,
             target: position,
+            waypoints: Vec::new(),
         },
         Health {
             health,
@@ -71,3 +71,5 @@ impl Display for LevelIndexError {
         Ok(())
     }
 }
+
+impl std::error::Error for LevelIndexError {}
@@ -0,0 +1,170 @@
+use nalgebra::DMatrix;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::war_assignment::{
+    StarsDistribution,
+    hungarian,
+};
+
+/// Limit of local search passes improving assignment made by
+/// [`assign_war_attacks`].
+const LOCAL_SEARCH_PASSES: usize = 10;
+/// Expected stars improvement local search move has to make.
+const LOCAL_SEARCH_MIN_IMPROVEMENT: f32 = 1e-4;
+
+/// Attack of clan member on enemy base.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WarAttack {
+    pub member: usize,
+    pub base: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WarAssignment {
+    pub attacks: Vec<WarAttack>,
+    /// Stars of the best attack on each base.
+    pub bases: Vec<StarsDistribution>,
+    /// Expected total stars of war.
+    pub expected_stars: f32,
+}
+
+/// Stars of the best attack on each base, attacks are independent.
+fn base_distributions(
+    stars: &[Vec<StarsDistribution>],
+    attacks: &[WarAttack],
+    bases_count: usize,
+) -> Vec<StarsDistribution> {
+    let mut result = vec![StarsDistribution::NO_STARS; bases_count];
+
+    for attack in attacks {
+        result[attack.base] = result[attack.base].max(&stars[attack.member][attack.base]);
+    }
+
+    result
+}
+
+fn expected_stars(bases: &[StarsDistribution]) -> f32 {
+    bases.iter().map(StarsDistribution::expected).sum()
+}
+
+/// Assigns up to `attacks_per_member` attacks of each member to bases,
+/// maximizing expected total stars, where base is worth the stars of its best
+/// attack. `stars[member][base]` is distribution of member's attack on base.
+///
+/// Members first attack different bases, which is optimal (Hungarian
+/// algorithm) if there is one attack per member and no more members than
+/// bases. The rest of attacks are cleanup hits added greedily by expected
+/// stars gain, then attacks are moved between bases while it improves
+/// expected stars. Attacks which can't gain stars are not made.
+pub fn assign_war_attacks(
+    stars: &[Vec<StarsDistribution>],
+    attacks_per_member: usize,
+) -> WarAssignment {
+    let members_count = stars.len();
+    let bases_count = stars.first().map_or(0, Vec::len);
+    let mut attacks = Vec::new();
+
+    if attacks_per_member > 0 {
+        let expected = DMatrix::from_fn(members_count, bases_count, |member, base| {
+            stars[member][base].expected()
+        });
+
+        for (member, base) in hungarian(&expected).into_iter().enumerate() {
+            if let Some(base) = base
+                && expected[(member, base)] > 0.0
+            {
+                attacks.push(WarAttack { member, base });
+            }
+        }
+    }
+
+    let attacks_count = |attacks: &[WarAttack], member: usize| {
+        attacks
+            .iter()
+            .filter(|attack| attack.member == member)
+            .count()
+    };
+    let attacked = |attacks: &[WarAttack], member: usize, base: usize| {
+        attacks.contains(&WarAttack { member, base })
+    };
+
+    // cleanup hits
+    loop {
+        let bases = base_distributions(stars, &attacks, bases_count);
+        let mut best = None;
+        let mut best_gain = 0.0;
+
+        for (member, member_stars) in stars.iter().enumerate() {
+            if attacks_count(&attacks, member) >= attacks_per_member {
+                continue;
+            }
+
+            for (base, base_stars) in bases.iter().enumerate() {
+                if attacked(&attacks, member, base) {
+                    continue;
+                }
+
+                let gain = base_stars.max(&member_stars[base]).expected() - base_stars.expected();
+
+                if gain > best_gain {
+                    best = Some(WarAttack { member, base });
+                    best_gain = gain;
+                }
+            }
+        }
+
+        let Some(attack) = best else {
+            break;
+        };
+
+        attacks.push(attack);
+    }
+
+    let mut current = expected_stars(&base_distributions(stars, &attacks, bases_count));
+
+    for _ in 0..LOCAL_SEARCH_PASSES {
+        let mut improved = false;
+
+        for i in 0..attacks.len() {
+            let WarAttack { member, base } = attacks[i];
+
+            for new_base in 0..bases_count {
+                if attacked(&attacks, member, new_base) {
+                    continue;
+                }
+
+                attacks[i].base = new_base;
+
+                let new = expected_stars(&base_distributions(stars, &attacks, bases_count));
+
+                if new > current + LOCAL_SEARCH_MIN_IMPROVEMENT {
+                    current = new;
+                    improved = true;
+
+                    break;
+                }
+
+                attacks[i].base = base;
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    attacks.sort_unstable_by_key(|attack| (attack.member, attack.base));
+
+    let bases = base_distributions(stars, &attacks, bases_count);
+
+    WarAssignment {
+        attacks,
+        expected_stars: expected_stars(&bases),
+        bases,
+    }
+}
@@ -0,0 +1,91 @@
+use nalgebra::DMatrix;
+
+/// Assignment of rows to columns maximizing sum of `values`, each row and
+/// column is used at most once. Returns column of each row, [`None`] for rows
+/// left out if there are more rows than columns.
+pub fn hungarian(values: &DMatrix<f32>) -> Vec<Option<usize>> {
+    if values.nrows() > values.ncols() {
+        let mut result = vec![None; values.nrows()];
+
+        for (column, row) in hungarian(&values.transpose()).into_iter().enumerate() {
+            if let Some(row) = row {
+                result[row] = Some(column);
+            }
+        }
+
+        return result;
+    }
+
+    // minimization with potentials, rows and columns are 1-indexed, 0 is a
+    // fake column rows are matched with before augmentation
+    let rows = values.nrows();
+    let columns = values.ncols();
+    let cost = |row: usize, column: usize| -values[(row - 1, column - 1)] as f64;
+    let mut row_potentials = vec![0.0; rows + 1];
+    let mut column_potentials = vec![0.0; columns + 1];
+    let mut column_rows = vec![0; columns + 1];
+    let mut previous_columns = vec![0; columns + 1];
+
+    for row in 1..=rows {
+        let mut min_reduced_costs = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+        let mut column = 0;
+
+        column_rows[0] = row;
+
+        while column_rows[column] != 0 {
+            used[column] = true;
+
+            let current_row = column_rows[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+
+            for j in 1..=columns {
+                if used[j] {
+                    continue;
+                }
+
+                let reduced_cost =
+                    cost(current_row, j) - row_potentials[current_row] - column_potentials[j];
+
+                if reduced_cost < min_reduced_costs[j] {
+                    min_reduced_costs[j] = reduced_cost;
+                    previous_columns[j] = column;
+                }
+
+                if min_reduced_costs[j] < delta {
+                    delta = min_reduced_costs[j];
+                    next_column = j;
+                }
+            }
+
+            for j in 0..=columns {
+                if used[j] {
+                    row_potentials[column_rows[j]] += delta;
+                    column_potentials[j] -= delta;
+                } else {
+                    min_reduced_costs[j] -= delta;
+                }
+            }
+
+            column = next_column;
+        }
+
+        while column != 0 {
+            let previous_column = previous_columns[column];
+
+            column_rows[column] = column_rows[previous_column];
+            column = previous_column;
+        }
+    }
+
+    let mut result = vec![None; rows];
+
+    for column in 1..=columns {
+        if column_rows[column] != 0 {
+            result[column_rows[column] - 1] = Some(column - 1);
+        }
+    }
+
+    result
+}
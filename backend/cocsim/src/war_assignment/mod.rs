@@ -0,0 +1,16 @@
+mod assign_war_attacks;
+mod hungarian;
+mod stars_distribution;
+mod war_stars_distributions;
+
+pub use assign_war_attacks::{
+    WarAssignment,
+    WarAttack,
+    assign_war_attacks,
+};
+pub use hungarian::hungarian;
+pub use stars_distribution::StarsDistribution;
+pub use war_stars_distributions::{
+    WarMember,
+    war_stars_distributions,
+};
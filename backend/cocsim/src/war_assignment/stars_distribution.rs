@@ -0,0 +1,60 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attack_optimizer::AttackPlanExecutionStats;
+
+/// Probabilities of getting 0, 1, 2 and 3 stars.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+pub struct StarsDistribution(pub [f32; 4]);
+
+impl StarsDistribution {
+    /// Base which wasn't attacked.
+    pub const NO_STARS: Self = Self([1.0, 0.0, 0.0, 0.0]);
+
+    /// Distribution of stars of weighted executions.
+    pub fn from_stats(stats: &AttackPlanExecutionStats) -> Self {
+        let mut result = [0.0; 4];
+        let mut weights_sum = 0.0;
+
+        for execution in &stats.executions {
+            result[execution.stars as usize] += execution.weight;
+            weights_sum += execution.weight;
+        }
+
+        Self(result.map(|probability| probability / weights_sum))
+    }
+
+    pub fn expected(&self) -> f32 {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(stars, probability)| stars as f32 * probability)
+            .sum()
+    }
+
+    /// Distribution of the best of two independent attacks on the same base,
+    /// war counts only the best one.
+    pub fn max(&self, other: &Self) -> Self {
+        let mut result = [0.0; 4];
+        let mut cdf = 0.0;
+        let mut self_cdf = 0.0;
+        let mut other_cdf = 0.0;
+
+        for ((probability, self_probability), other_probability) in
+            result.iter_mut().zip(self.0).zip(other.0)
+        {
+            self_cdf += self_probability;
+            other_cdf += other_probability;
+
+            let max_cdf = self_cdf * other_cdf;
+
+            *probability = max_cdf - cdf;
+            cdf = max_cdf;
+        }
+
+        Self(result)
+    }
+}
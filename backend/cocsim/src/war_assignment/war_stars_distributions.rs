@@ -0,0 +1,49 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameConfig,
+    ValidatedMap,
+    attack_optimizer::{
+        Army,
+        InitialAttackPlan,
+        execute_attack_plan_batch,
+    },
+    war_assignment::StarsDistribution,
+};
+
+/// Clan member taking part in war.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WarMember {
+    pub army: Army,
+    /// Plan template member uses on every base.
+    pub plan: InitialAttackPlan,
+}
+
+/// Stars distribution of each member's attack on each base,
+/// `result[member][base]`. Every attack is executed `executions_count`
+/// times.
+pub fn war_stars_distributions(
+    members: &[WarMember],
+    bases: &[ValidatedMap],
+    config: &GameConfig,
+    executions_count: usize,
+    tps: usize,
+) -> anyhow::Result<Vec<Vec<StarsDistribution>>> {
+    members
+        .iter()
+        .map(|member| {
+            let stats = execute_attack_plan_batch(
+                bases,
+                config,
+                |map| member.plan.clamped_executor_actions(&member.army, map),
+                executions_count,
+                tps,
+            )?;
+
+            Ok(stats.iter().map(StarsDistribution::from_stats).collect())
+        })
+        .collect()
+}
@@ -118,6 +118,8 @@ impl Display for HousingSpaceError {
     }
 }
 
+impl std::error::Error for HousingSpaceError {}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WithCount<T: WithHousingSpace> {
     pub value: T,
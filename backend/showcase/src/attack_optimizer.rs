@@ -3,12 +3,17 @@ mod utils;
 
 use cocsim::{
     Game,
+    GameConfig,
     WithCount,
     attack_optimizer::{
         Army,
+        AttackOptimizer,
         AttackPlanExecutor,
         execute_attack_plan,
-        v3::AttackPlan,
+        v3::{
+            AttackPlan,
+            LipoAttackOptimizer,
+        },
     },
     consts::{
         ATTACK_PLAN_EXECUTIONS_COUNT,
@@ -27,11 +32,6 @@ use cocsim::{
         DragonModel,
     },
 };
-use fastrand::Rng;
-use gomez::{
-    OptimizerDriver,
-    algo::Lipo,
-};
 use rand_pcg::Pcg64Mcg;
 
 use crate::utils::macroquad_run_game;
@@ -108,30 +108,31 @@ fn main() {
     };
 
     let (map, map_image) = load_test_map("Single Player/No Flight Zone").unwrap();
+    let config = GameConfig::default();
 
-    let attack_plan = AttackPlan {
-        map: map.clone(),
-        army: army.clone(),
-    };
-
-    let mut optimizer = OptimizerDriver::builder(&attack_plan)
-        .with_algo(|f, domain| Lipo::new(f, domain, Rng::new()))
-        .build();
-
-    let mut x = optimizer.next().unwrap();
+    let mut optimizer = LipoAttackOptimizer::new(
+        AttackPlan {
+            map: map.clone(),
+            army: army.clone(),
+            config: config.clone(),
+        },
+        None,
+        1,
+    );
 
-    println!("0 {} {:?}", x.1, x.0);
+    for i in 0..=200 {
+        optimizer.step();
 
-    for i in 0..200 {
-        x = optimizer.next().unwrap();
+        let (x, stats) = optimizer.best_x().unwrap();
 
-        println!("{i} {} {:?}", x.1, x.0);
+        println!("{i} {} {:?}", stats.score, x);
     }
 
-    let best_plan_actions = attack_plan.executor_actions(x.0.iter().cloned());
+    let (best_plan_actions, _) = optimizer.best().unwrap();
 
     let score = execute_attack_plan(
         &map,
+        &config,
         &best_plan_actions,
         ATTACK_PLAN_EXECUTIONS_COUNT,
         ATTACK_PLAN_EXECUTOR_TPS,
@@ -145,8 +146,8 @@ fn main() {
         score.avg_percentage_destroyed
     );
 
-    let game = Game::new(&map, true, Some(Pcg64Mcg::new(RNG_INITIAL_STATE)));
-    let mut plan_executor = AttackPlanExecutor::new(best_plan_actions);
+    let game = Game::new(&map, &config, true, Some(Pcg64Mcg::new(RNG_INITIAL_STATE)));
+    let mut plan_executor = AttackPlanExecutor::new(best_plan_actions, &map, &config);
 
     macroquad_run_game(
         game,
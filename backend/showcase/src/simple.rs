@@ -3,6 +3,7 @@ mod utils;
 
 use cocsim::{
     Game,
+    GameConfig,
     spells::{
         HasteSpellModel,
         HealingSpellModel,
@@ -20,7 +21,7 @@ use crate::utils::macroquad_run_game;
 fn main() {
     let (map, map_image) = load_test_map("Single Player/No Flight Zone").unwrap();
 
-    let mut game = Game::new(&map, true, None);
+    let mut game = Game::new(&map, &GameConfig::default(), true, None);
 
     for _i in 0..10 {
         game.spawn_attack_unit(
@@ -0,0 +1,26 @@
+use api_base::AssignWarAttacksRequest;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use tokio::task::spawn_blocking;
+
+use crate::webserver_error::WebserverError;
+
+/// Stars distributions and assignment of attacks, see
+/// [`api_base::assign_war_attacks`]. Bad request if bases or members are
+/// invalid.
+pub async fn assign_war_attacks(
+    Json(request): Json<AssignWarAttacksRequest>,
+) -> Result<Response, WebserverError> {
+    let result = spawn_blocking(|| api_base::assign_war_attacks(request)).await?;
+
+    Ok(match result {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}")).into_response(),
+    })
+}
@@ -0,0 +1,25 @@
+use api_base::EvaluateAttackPlanRequest;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use tokio::task::spawn_blocking;
+
+use crate::webserver_error::WebserverError;
+
+/// Stats of plan on each map, in request order. Bad request if maps or plan
+/// are invalid.
+pub async fn evaluate_attack_plan(
+    Json(request): Json<EvaluateAttackPlanRequest>,
+) -> Result<Response, WebserverError> {
+    let result = spawn_blocking(|| api_base::evaluate_attack_plan(request)).await?;
+
+    Ok(match result {
+        Ok(stats) => Json(stats).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}")).into_response(),
+    })
+}
@@ -0,0 +1,103 @@
+use api_base::{
+    InstantClock,
+    OptimizeAttackJob,
+    OptimizeAttackJobRequest,
+};
+use axum::{
+    Json,
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use serde_json::json;
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+use crate::{
+    jobs::{
+        JobStatus,
+        Jobs,
+    },
+    webserver_error::WebserverError,
+};
+
+/// Starts optimization job, returns its id. Same job as started by
+/// [`crate::api::optimize_attack`], but with all parameters in one request.
+pub async fn create_job(
+    State(jobs): State<Jobs>,
+    Json(request): Json<OptimizeAttackJobRequest>,
+) -> Result<Response, WebserverError> {
+    let optimize_attack_job = match spawn_blocking(|| {
+        OptimizeAttackJob::from_request(request, Box::new(InstantClock::default()))
+    })
+    .await?
+    {
+        Ok(optimize_attack_job) => optimize_attack_job,
+        Err(err) => return Ok((StatusCode::BAD_REQUEST, format!("{err:#}")).into_response()),
+    };
+    let (id, _) = jobs.start(optimize_attack_job);
+
+    Ok((StatusCode::CREATED, Json(json!({ "id": id.to_string() }))).into_response())
+}
+
+/// Status, progress messages, stats and army of the best plan found so far and
+/// Pareto front of multi-objective stage.
+pub async fn get_job(
+    Path(id): Path<Uuid>,
+    State(jobs): State<Jobs>,
+) -> Result<Response, WebserverError> {
+    let Some(job) = spawn_blocking(move || jobs.get(id)).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let state = job.state.borrow();
+
+    Ok(Json(json!({
+        "status": state.status,
+        "progress": state.progress,
+        "bestPlanStats": state.best_plan_stats,
+        "army": state.army,
+        "paretoFront": state.pareto_front,
+    }))
+    .into_response())
+}
+
+/// Frames of the best plan, same as `result` message of
+/// [`crate::api::optimize_attack`]. Conflict if job isn't done yet.
+pub async fn get_job_result(
+    Path(id): Path<Uuid>,
+    State(jobs): State<Jobs>,
+) -> Result<Response, WebserverError> {
+    let Some(job) = spawn_blocking(move || jobs.get(id)).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let result = {
+        let state = job.state.borrow();
+
+        match (state.status, &state.result) {
+            (JobStatus::Done, Some(result)) => result.clone(),
+            _ => return Ok(StatusCode::CONFLICT.into_response()),
+        }
+    };
+
+    Ok(Json(&*result).into_response())
+}
+
+/// Stops job and deletes it.
+pub async fn cancel_job(
+    Path(id): Path<Uuid>,
+    State(jobs): State<Jobs>,
+) -> Result<StatusCode, WebserverError> {
+    let found = spawn_blocking(move || jobs.cancel(id)).await??;
+
+    Ok(if found {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    })
+}
@@ -1,13 +1,28 @@
+mod assign_war_attacks;
 mod compose_base_images;
+mod evaluate_attack_plan;
 mod get_game_types;
 mod get_showcase_attack;
 mod get_showcase_attack_base_image;
+mod jobs;
 mod optimize_attack;
 mod reverse_projection;
 
+pub use assign_war_attacks::assign_war_attacks;
 pub use compose_base_images::compose_base_images;
+pub use evaluate_attack_plan::evaluate_attack_plan;
 pub use get_game_types::get_game_types;
 pub use get_showcase_attack::get_showcase_attack;
 pub use get_showcase_attack_base_image::get_showcase_attack_base_image;
-pub use optimize_attack::optimize_attack;
+pub use jobs::{
+    cancel_job,
+    create_job,
+    get_job,
+    get_job_result,
+};
+pub use optimize_attack::{
+    extend_optimize_attack_job,
+    follow_optimize_attack_job,
+    optimize_attack,
+};
 pub use reverse_projection::reverse_projection;
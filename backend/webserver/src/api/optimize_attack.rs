@@ -1,80 +1,194 @@
-use api_base::SendRecvError;
+use std::sync::Arc;
+
+use anyhow::Context;
+use api_base::{
+    InstantClock,
+    OptimizeAttackClientMessage,
+    OptimizeAttackServerMessage,
+};
 use axum::{
+    Json,
     extract::{
+        Path,
+        State,
         WebSocketUpgrade,
         ws::{
             Message,
             WebSocket,
         },
     },
-    response::Response,
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
 };
 use log::warn;
+use serde::Deserialize;
 use tokio::{
     select,
     task::spawn_blocking,
 };
+use uuid::Uuid;
+
+use crate::{
+    jobs::{
+        Job,
+        JobStatus,
+        Jobs,
+    },
+    webserver_error::WebserverError,
+};
 
-pub async fn optimize_attack(ws: WebSocketUpgrade) -> Response {
-    ws.on_upgrade(async |socket| {
-        if let Err(err) = optimize_attack_internal(socket).await {
+/// Starts job from the same messages as [`api_base::optimize_attack`] and
+/// follows it. Job continues if socket is closed, its id is sent first.
+pub async fn optimize_attack(ws: WebSocketUpgrade, State(jobs): State<Jobs>) -> Response {
+    ws.on_upgrade(async move |socket| {
+        if let Err(err) = optimize_attack_internal(socket, jobs).await {
             warn!("optimize_attack_internal finished with error: {err:#?}");
         }
     })
 }
 
-async fn optimize_attack_internal(mut socket: WebSocket) -> anyhow::Result<()> {
-    let (send_tx, mut send_rx) = tokio::sync::mpsc::channel::<String>(10);
-    let (recv_tx, mut recv_rx) = tokio::sync::mpsc::channel::<String>(10);
+/// Reconnects to job started by [`optimize_attack`]: sends all progress
+/// messages and result when job is done.
+pub async fn follow_optimize_attack_job(
+    ws: WebSocketUpgrade,
+    Path(id): Path<Uuid>,
+    State(jobs): State<Jobs>,
+) -> Result<Response, WebserverError> {
+    let Some(job) = spawn_blocking(move || jobs.get(id)).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
 
-    let send = move |s: String| match send_tx.blocking_send(s) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(SendRecvError::Cancel),
+    Ok(ws.on_upgrade(async move |socket| {
+        if let Err(err) = follow_job(socket, job).await {
+            warn!("follow_job finished with error: {err:#?}");
+        }
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ExtendOptimizeAttackJobRequest {
+    steps: usize,
+}
+
+/// Adds steps to job, done jobs are continued. Bad request if steps are zero
+/// or job would have too many steps.
+pub async fn extend_optimize_attack_job(
+    Path(id): Path<Uuid>,
+    State(jobs): State<Jobs>,
+    Json(request): Json<ExtendOptimizeAttackJobRequest>,
+) -> Result<Response, WebserverError> {
+    Ok(
+        match spawn_blocking(move || jobs.extend(id, request.steps)).await?? {
+            Some(Ok(())) => StatusCode::OK.into_response(),
+            Some(Err(err)) => (StatusCode::BAD_REQUEST, format!("{err:#}")).into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+    )
+}
+
+async fn optimize_attack_internal(mut socket: WebSocket, jobs: Jobs) -> anyhow::Result<()> {
+    let message = loop {
+        if let Message::Text(text) = socket.recv().await.context("Socket closed")?? {
+            break text.as_str().to_owned();
+        }
     };
-    let recv = move || match recv_rx.blocking_recv() {
-        Some(s) => Ok(s),
-        None => Err(SendRecvError::Cancel),
+    let optimize_attack_job = spawn_blocking(move || {
+        match serde_json::from_str::<OptimizeAttackClientMessage>(&message)? {
+            OptimizeAttackClientMessage::Start(start) => {
+                start.into_job(Box::new(InstantClock::default()))
+            }
+        }
+    })
+    .await?;
+    let optimize_attack_job = match optimize_attack_job {
+        Ok(optimize_attack_job) => optimize_attack_job,
+        Err(err) => {
+            send_message(
+                &mut socket,
+                OptimizeAttackServerMessage::Error {
+                    message: format!("{err:#}"),
+                },
+            )
+            .await?;
+            socket.send(Message::Close(None)).await?;
+
+            return Ok(());
+        }
     };
+    let (id, job) = jobs.start(optimize_attack_job);
 
-    let mut join_handle = spawn_blocking(|| api_base::optimize_attack(send, recv));
-    let mut join_handle_awaited = false;
+    send_message(
+        &mut socket,
+        OptimizeAttackServerMessage::Job { id: id.to_string() },
+    )
+    .await?;
+
+    follow_job(socket, job).await
+}
+
+async fn send_message(
+    socket: &mut WebSocket,
+    message: OptimizeAttackServerMessage,
+) -> anyhow::Result<()> {
+    socket.send(Message::Text(message.to_json().into())).await?;
+
+    Ok(())
+}
+
+async fn follow_job(mut socket: WebSocket, job: Arc<Job>) -> anyhow::Result<()> {
+    let mut state = job.state.subscribe();
+    let mut progress_sent = 0;
 
     loop {
+        let (progress, status, result) = {
+            let state = state.borrow_and_update();
+
+            (
+                state.progress[progress_sent..].to_vec(),
+                state.status,
+                state.result.clone(),
+            )
+        };
+
+        progress_sent += progress.len();
+
+        for progress in progress {
+            send_message(&mut socket, OptimizeAttackServerMessage::Progress(progress)).await?;
+        }
+
+        let last_message = match (status, result) {
+            (JobStatus::Done, Some(result)) => Some(OptimizeAttackServerMessage::Result {
+                result: (*result).clone(),
+            }),
+            (JobStatus::Failed, _) => Some(OptimizeAttackServerMessage::Error {
+                message: "Optimization job failed".to_owned(),
+            }),
+            (JobStatus::Cancelled, _) => Some(OptimizeAttackServerMessage::Error {
+                message: "Optimization job was cancelled".to_owned(),
+            }),
+            _ => None,
+        };
+
+        if let Some(last_message) = last_message {
+            send_message(&mut socket, last_message).await?;
+
+            break;
+        }
+
         select! {
-            result = &mut join_handle => {
-                result??;
-                join_handle_awaited = true;
-
-                break;
-            },
-            value_to_send = send_rx.recv() => {
-                match value_to_send {
-                    None => break,
-                    Some(value_to_send) => {
-                        socket.send(Message::Text(value_to_send.into())).await?;
-                    }
-                }
-            }
-            recv_value = socket.recv() => {
-                match recv_value {
-                    None => break,
-                    Some(recv_value) => {
-                        recv_tx.send(recv_value?.into_text()?.as_str().to_owned()).await?;
-                    }
+            changed = state.changed() => changed?,
+            message = socket.recv() => {
+                // client only listens, so any message is ignored
+                if message.is_none() {
+                    return Ok(());
                 }
             }
         }
     }
 
-    // dropping these causes send and recv to return Err(SendRecvError::Cancel) in
-    // api_base::optimize_attack
-    drop(send_rx);
-    drop(recv_tx);
-
-    if !join_handle_awaited {
-        join_handle.await??;
-    }
-
     socket.send(Message::Close(None)).await?;
 
     Ok(())
@@ -0,0 +1,443 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+    },
+};
+
+use api_base::{
+    InstantClock,
+    OptimizeAttackJob,
+    OptimizeAttackJobCheckpoint,
+    OptimizeAttackProgress,
+    ParetoFrontPlan,
+};
+use cocsim::attack_optimizer::{
+    Army,
+    AttackPlanExecutionStats,
+};
+use log::warn;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+use tokio::{
+    sync::{
+        Semaphore,
+        watch,
+    },
+    task::spawn_blocking,
+};
+use uuid::Uuid;
+
+/// Environment variable with directory where job checkpoints are saved.
+const JOBS_DIR_ENV: &str = "COCSIM_JOBS_DIR";
+const DEFAULT_JOBS_DIR: &str = "jobs";
+/// Environment variable with number of jobs running at the same time.
+const JOB_WORKERS_ENV: &str = "COCSIM_JOB_WORKERS";
+/// Each job already uses all cores for plan executions.
+const DEFAULT_JOB_WORKERS: usize = 1;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    /// Waiting for free worker.
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Job has runner which will change its status.
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Running)
+    }
+}
+
+pub struct JobState {
+    pub status: JobStatus,
+    /// Progress of all steps, including ones done before server restart.
+    pub progress: Vec<OptimizeAttackProgress>,
+    pub best_plan_stats: Option<AttackPlanExecutionStats>,
+    /// Army of the best plan, composition found by `v1-composition` stages.
+    pub army: Army,
+    /// Empty if optimizer of the current stage isn't multi-objective.
+    pub pareto_front: Vec<ParetoFrontPlan>,
+    /// Frames of the best plan, present if job is done.
+    pub result: Option<Arc<Value>>,
+}
+
+pub struct Job {
+    pub state: watch::Sender<JobState>,
+    /// Steps of all pipeline stages, including ones requested by
+    /// [`Jobs::extend`].
+    steps: AtomicUsize,
+    /// Steps requested by [`Jobs::extend`] which runner hasn't added yet.
+    extra_steps: AtomicUsize,
+    /// Set by [`Jobs::cancel`], runner deletes job when it sees it.
+    cancelled: AtomicBool,
+}
+
+impl Job {
+    fn new(optimize_attack_job: &OptimizeAttackJob, progress: Vec<OptimizeAttackProgress>) -> Self {
+        Self {
+            state: watch::Sender::new(JobState {
+                status: JobStatus::Queued,
+                progress,
+                best_plan_stats: optimize_attack_job.best_plan_stats(),
+                army: optimize_attack_job.army(),
+                pareto_front: optimize_attack_job.pareto_front(),
+                result: None,
+            }),
+            steps: AtomicUsize::new(optimize_attack_job.steps()),
+            extra_steps: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Saved after every step, so job can be continued after server restart.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JobFile {
+    checkpoint: OptimizeAttackJobCheckpoint,
+    progress: Vec<OptimizeAttackProgress>,
+}
+
+/// Optimization jobs running independently of client connections.
+#[derive(Clone)]
+pub struct Jobs(Arc<JobsInner>);
+
+struct JobsInner {
+    dir: PathBuf,
+    jobs: Mutex<HashMap<Uuid, Arc<Job>>>,
+    workers: Arc<Semaphore>,
+}
+
+impl Jobs {
+    pub fn new() -> anyhow::Result<Self> {
+        let dir = PathBuf::from(
+            std::env::var(JOBS_DIR_ENV).unwrap_or_else(|_| DEFAULT_JOBS_DIR.to_owned()),
+        );
+
+        let workers = match std::env::var(JOB_WORKERS_ENV) {
+            Ok(workers) => workers.parse()?,
+            Err(_) => DEFAULT_JOB_WORKERS,
+        };
+
+        anyhow::ensure!(workers > 0, "{JOB_WORKERS_ENV} should be positive");
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self(Arc::new(JobsInner {
+            dir,
+            jobs: Mutex::new(HashMap::new()),
+            workers: Arc::new(Semaphore::new(workers)),
+        })))
+    }
+
+    pub fn start(&self, optimize_attack_job: OptimizeAttackJob) -> (Uuid, Arc<Job>) {
+        let id = Uuid::new_v4();
+        let job = Arc::new(Job::new(&optimize_attack_job, Vec::new()));
+
+        self.0.jobs.lock().unwrap().insert(id, job.clone());
+
+        self.spawn_runner(id, job.clone(), optimize_attack_job);
+
+        (id, job)
+    }
+
+    /// Job started by this server or saved to checkpoint before restart.
+    /// Saved jobs are continued.
+    pub fn get(&self, id: Uuid) -> Option<Arc<Job>> {
+        if let Some(job) = self.0.jobs.lock().unwrap().get(&id) {
+            return Some(job.clone());
+        }
+
+        if !self.path(id).exists() {
+            return None;
+        }
+
+        // loaded without lock, so other jobs aren't blocked while it's
+        // restored from checkpoint
+
+        let (optimize_attack_job, progress) = match self.load(id).and_then(|job_file| {
+            Ok((
+                OptimizeAttackJob::from_checkpoint(
+                    job_file.checkpoint,
+                    Box::new(InstantClock::default()),
+                )?,
+                job_file.progress,
+            ))
+        }) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                warn!("Job {id} can't be loaded: {err:#}");
+
+                return None;
+            }
+        };
+        let job = {
+            let mut jobs = self.0.jobs.lock().unwrap();
+
+            // job can be loaded by another request or cancelled meanwhile,
+            // cancelled job is deleted under the same lock
+            if let Some(job) = jobs.get(&id) {
+                return Some(job.clone());
+            }
+
+            if !self.path(id).exists() {
+                return None;
+            }
+
+            let job = Arc::new(Job::new(&optimize_attack_job, progress));
+
+            jobs.insert(id, job.clone());
+
+            job
+        };
+
+        self.spawn_runner(id, job.clone(), optimize_attack_job);
+
+        Some(job)
+    }
+
+    /// Adds steps of the last pipeline stage to job, done jobs are continued
+    /// from the last checkpoint. Returns [`None`] if job doesn't exist or is
+    /// cancelled, error inside if job can't be extended by `steps` (see
+    /// [`OptimizeAttackJob::extended_steps`]).
+    pub fn extend(&self, id: Uuid, steps: usize) -> anyhow::Result<Option<anyhow::Result<()>>> {
+        let Some(job) = self.get(id) else {
+            return Ok(None);
+        };
+        let mut result = None;
+        // runner checks extra steps under the same lock before finishing
+        let restart = job.state.send_if_modified(|state| {
+            if state.status == JobStatus::Cancelled {
+                return false;
+            }
+
+            let extended =
+                job.steps
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |job_steps| {
+                        OptimizeAttackJob::extended_steps(job_steps, steps).ok()
+                    });
+
+            if let Err(job_steps) = extended {
+                result = Some(OptimizeAttackJob::extended_steps(job_steps, steps).map(|_| ()));
+
+                return false;
+            }
+
+            job.extra_steps.fetch_add(steps, Ordering::SeqCst);
+            result = Some(Ok(()));
+
+            if state.status.is_active() {
+                false
+            } else {
+                state.status = JobStatus::Queued;
+                state.result = None;
+
+                true
+            }
+        });
+
+        if restart {
+            let optimize_attack_job = OptimizeAttackJob::from_checkpoint(
+                self.load(id)?.checkpoint,
+                Box::new(InstantClock::default()),
+            )?;
+
+            self.spawn_runner(id, job, optimize_attack_job);
+        }
+
+        Ok(result)
+    }
+
+    /// Stops job and deletes its checkpoint. Returns `false` if job doesn't
+    /// exist.
+    pub fn cancel(&self, id: Uuid) -> anyhow::Result<bool> {
+        let Some(job) = self.get(id) else {
+            return Ok(false);
+        };
+        let mut active = false;
+
+        // runner checks cancellation under the same lock before finishing
+        job.state.send_if_modified(|state| {
+            if state.status == JobStatus::Cancelled {
+                return false;
+            }
+
+            job.cancelled.store(true, Ordering::SeqCst);
+            active = state.status.is_active();
+            state.status = JobStatus::Cancelled;
+            state.result = None;
+
+            true
+        });
+
+        // otherwise runner deletes job, so it doesn't save checkpoint again
+        if !active {
+            self.delete(id)?;
+        }
+
+        Ok(true)
+    }
+
+    fn delete(&self, id: Uuid) -> anyhow::Result<()> {
+        let mut jobs = self.0.jobs.lock().unwrap();
+        let path = self.path(id);
+
+        jobs.remove(&id);
+
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, id: Uuid) -> PathBuf {
+        self.0.dir.join(format!("{id}.json"))
+    }
+
+    fn load(&self, id: Uuid) -> anyhow::Result<JobFile> {
+        Ok(serde_json::from_slice(&fs::read(self.path(id))?)?)
+    }
+
+    fn save(&self, id: Uuid, job_file: &JobFile) -> anyhow::Result<()> {
+        let path = self.path(id);
+        let tmp_path = path.with_extension("json.tmp");
+
+        // rename is atomic, so interrupted write doesn't corrupt checkpoint
+        fs::write(&tmp_path, serde_json::to_vec(job_file)?)?;
+        fs::rename(tmp_path, path)?;
+
+        Ok(())
+    }
+
+    fn spawn_runner(&self, id: Uuid, job: Arc<Job>, optimize_attack_job: OptimizeAttackJob) {
+        let jobs = self.clone();
+        let runner_jobs = self.clone();
+        let runner_job = job.clone();
+
+        tokio::spawn(async move {
+            let _worker = jobs
+                .0
+                .workers
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed");
+
+            job.state.send_if_modified(|state| {
+                if state.status == JobStatus::Queued {
+                    state.status = JobStatus::Running;
+
+                    true
+                } else {
+                    false
+                }
+            });
+
+            let result =
+                spawn_blocking(move || runner_jobs.run(id, &runner_job, optimize_attack_job)).await;
+
+            if let Err(err) = result
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            {
+                warn!("Job {id} failed: {err:#}");
+
+                job.state.send_if_modified(|state| {
+                    if state.status == JobStatus::Cancelled {
+                        false
+                    } else {
+                        state.status = JobStatus::Failed;
+
+                        true
+                    }
+                });
+
+                if job.cancelled.load(Ordering::SeqCst)
+                    && let Err(err) = jobs.delete(id)
+                {
+                    warn!("Job {id} can't be deleted: {err:#}");
+                }
+            }
+        });
+    }
+
+    fn run(
+        &self,
+        id: Uuid,
+        job: &Job,
+        mut optimize_attack_job: OptimizeAttackJob,
+    ) -> anyhow::Result<()> {
+        loop {
+            if job.cancelled.load(Ordering::SeqCst) {
+                return self.delete(id);
+            }
+
+            let extra_steps = job.extra_steps.swap(0, Ordering::SeqCst);
+
+            if extra_steps != 0 {
+                optimize_attack_job.extend(extra_steps);
+            }
+
+            if optimize_attack_job.is_done() {
+                let result = Arc::new(optimize_attack_job.render_best_plan()?);
+                let done = job.state.send_if_modified(|state| {
+                    if job.extra_steps.load(Ordering::SeqCst) == 0
+                        && !job.cancelled.load(Ordering::SeqCst)
+                    {
+                        state.status = JobStatus::Done;
+                        state.result = Some(result.clone());
+
+                        true
+                    } else {
+                        false
+                    }
+                });
+
+                if done {
+                    return Ok(());
+                }
+
+                continue;
+            }
+
+            let progress = optimize_attack_job.step()?;
+
+            let best_plan_stats = optimize_attack_job.best_plan_stats();
+            let army = optimize_attack_job.army();
+            let pareto_front = optimize_attack_job.pareto_front();
+
+            job.state.send_modify(|state| {
+                state.progress.push(progress);
+                state.best_plan_stats = best_plan_stats;
+                state.army = army;
+                state.pareto_front = pareto_front;
+            });
+
+            self.save(
+                id,
+                &JobFile {
+                    checkpoint: optimize_attack_job.checkpoint(),
+                    progress: job.state.borrow().progress.clone(),
+                },
+            )?;
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod api;
+mod jobs;
 mod webserver_error;
 
 #[cfg(not(feature = "publish"))]
@@ -20,7 +21,10 @@ use tower_http::{
     trace::TraceLayer,
 };
 
-use crate::api::*;
+use crate::{
+    api::*,
+    jobs::Jobs,
+};
 
 #[tokio::main]
 async fn main() {
@@ -36,14 +40,25 @@ async fn main() {
     let app = Router::new()
         .route("/api/compose-base-images", post(compose_base_images))
         .layer(DefaultBodyLimit::disable())
+        .route("/api/assign-war-attacks", post(assign_war_attacks))
+        .route("/api/evaluate-attack-plan", post(evaluate_attack_plan))
         .route("/api/get-game-types", get(get_game_types))
         .route(
             "/api/get-showcase-attack-base-image",
             get(get_showcase_attack_base_image),
         )
         .route("/api/get-showcase-attack", get(get_showcase_attack))
+        .route("/api/jobs", post(create_job))
+        .route("/api/jobs/{id}", get(get_job).delete(cancel_job))
+        .route("/api/jobs/{id}/result", get(get_job_result))
         .route("/api/optimize-attack", any(optimize_attack))
+        .route("/api/optimize-attack/{id}", any(follow_optimize_attack_job))
+        .route(
+            "/api/optimize-attack/{id}/extend",
+            post(extend_optimize_attack_job),
+        )
         .route("/api/reverse-projection", post(reverse_projection))
+        .with_state(Jobs::new().expect("Jobs directory should be created"))
         .layer(layers);
 
     #[cfg(feature = "publish")]
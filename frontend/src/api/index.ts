@@ -1,6 +1,9 @@
 import type { Frame, GameTypes } from "../types";
 import api from "./api_impl";
 
+// must match api_base::OPTIMIZE_ATTACK_PROTOCOL_VERSION
+const OPTIMIZE_ATTACK_PROTOCOL_VERSION = 1;
+
 type ApiStream = {
   send: (data: string) => void;
   close: () => void;
@@ -23,4 +26,5 @@ type Api = {
 };
 
 export default api;
+export { OPTIMIZE_ATTACK_PROTOCOL_VERSION };
 export type { ApiStream, ApiStreamConnector, Api };
@@ -1,14 +1,19 @@
 import { useContext, useEffect, useState } from "react";
 import { twJoin } from "tailwind-merge";
 
-import api, { type ApiStream } from "../api";
+import api, {
+  OPTIMIZE_ATTACK_PROTOCOL_VERSION,
+  type ApiStream
+} from "../api";
 import ArmyEditor from "../components/ArmyEditor";
 import GameRenderer from "../components/GameRenderer";
 import Header from "../components/Header";
+import IntegerNumberInput from "../components/IntegerNumberInput";
 import { GameTypesContext } from "../hooks/use-game-types";
 import type {
   Frame,
   Map,
+  OptimizeAttackClientMessage,
   OptimizeAttackMessage,
   SpellWithCount,
   UnitWithCount
@@ -21,6 +26,8 @@ const AttackOptimizerPage: React.FC = () => {
 
   const [units, setUnits] = useState<UnitWithCount[]>([]);
   const [spells, setSpells] = useState<SpellWithCount[]>([]);
+  // seconds, 0 if optimization isn't limited by time
+  const [timeBudget, setTimeBudget] = useState(0);
   const [optimizeAttackStream, setOptimizeAttackStream] = useState<
     ApiStream | undefined
   >(undefined);
@@ -63,17 +70,35 @@ const AttackOptimizerPage: React.FC = () => {
       setOptimizeAttackStream(
         api.optimizeAttack.connect(
           (stream) => {
-            stream.send(JSON.stringify(mapData!.map));
-            stream.send(JSON.stringify(units));
-            stream.send(JSON.stringify(spells));
+            const start: OptimizeAttackClientMessage = {
+              type: "start",
+              version: OPTIMIZE_ATTACK_PROTOCOL_VERSION,
+              map: mapData!.map,
+              units: units,
+              spells: spells,
+              timeBudget: timeBudget > 0 ? timeBudget : undefined
+            };
+
+            stream.send(JSON.stringify(start));
+            setMessageHistory((prev) =>
+              prev.concat("Attack optimization process started...")
+            );
           },
           (data) => {
             const message = JSON.parse(data) as OptimizeAttackMessage;
 
-            if (message.type === "progress") {
-              const progressMessage = `[${new Date().toLocaleTimeString()}] ${message.progress}`;
+            if (message.type === "job") {
+              setMessageHistory((prev) =>
+                prev.concat(`Optimization job ${message.id}`)
+              );
+            } else if (message.type === "progress") {
+              const progressMessage = `[${new Date().toLocaleTimeString()}] Gen. #${message.generation} best plan finished in ${message.minTimeElapsed.toFixed(1)} <= ${message.avgTimeElapsed.toFixed(1)} <= ${message.maxTimeElapsed.toFixed(1)} seconds, score ${message.bestScore.toFixed(1)} ± ${message.bestScoreStandardError.toFixed(1)} (${message.samplesCount} runs), ${message.elapsed.toFixed(0)} s elapsed, ${message.simulationsPerSecond.toFixed(0)} sim/s`;
 
               setMessageHistory((prev) => prev.concat(progressMessage));
+            } else if (message.type === "error") {
+              setMessageHistory((prev) =>
+                prev.concat(`Attack optimization failed: ${message.message}`)
+              );
             } else {
               setGameRendererFrames(message.result);
             }
@@ -116,6 +141,15 @@ const AttackOptimizerPage: React.FC = () => {
                     setItems={setSpells}
                     types={gameTypes.spells}
                   />
+                  <div className="flex items-center gap-2">
+                    <IntegerNumberInput
+                      text="Time budget (seconds, 0 for none):"
+                      min={0}
+                      max={3600}
+                      defaultValue={timeBudget}
+                      onChange={setTimeBudget}
+                    />
+                  </div>
                   <button
                     onClick={onOptimizeAttackButtonClick}
                     className="w-min cursor-pointer bg-blue-400 px-2 py-1 text-sm font-bold text-white hover:bg-blue-600"
@@ -2,8 +2,12 @@ type Map = {
   baseSize: number;
   borderSize: number;
   buildings: Building[];
+  trapZones?: TrapZone[];
+  dropZoneRules?: DropZoneRulesPreset;
 };
 
+type DropZoneRulesPreset = "classic" | "modern";
+
 type WithName = {
   name: string;
 };
@@ -35,6 +39,15 @@ type ClanCastleBuilding = GenericBuilding & {
  */
 type Building = GenericBuilding | ClanCastleBuilding;
 
+/**
+ * Traps with unknown positions, placed at random weighted tiles on each simulation.
+ */
+type TrapZone = {
+  trap: { name: string; level: number };
+  count: number;
+  tiles: { x: number; y: number; weight: number }[];
+};
+
 /**
  * Building type type. Returned by getBuildingTypes() api to get all possible buildings.
  */
@@ -129,22 +142,76 @@ type Frame = {
   entities: Shape[];
 };
 
+type AttackObjective = "destructionAndTime" | "destruction";
+
+type OptimizeAttackStage = {
+  optimizer: string;
+  params?: Record<string, number>;
+  steps: number;
+};
+
+// models `v1-composition` stages compose army of
+type AvailableArmy = {
+  units: Unit[];
+  spells: Spell[];
+  unitsHousingSpace: number;
+  spellsHousingSpace: number;
+};
+
+type OptimizeAttackClientMessage = {
+  type: "start";
+  version: number;
+  map: Map;
+  units: UnitWithCount[];
+  spells: SpellWithCount[];
+  initialPlan?: unknown;
+  availableArmy?: AvailableArmy;
+  pipeline?: OptimizeAttackStage[];
+  objective?: AttackObjective;
+  // seconds
+  timeBudget?: number;
+};
+
+type OptimizeAttackProgress = {
+  generation: number;
+  bestScore: number;
+  bestScoreStandardError: number;
+  minTimeElapsed: number;
+  avgTimeElapsed: number;
+  maxTimeElapsed: number;
+  avgPercentageDestroyed: number;
+  samplesCount: number;
+  // seconds since optimization start
+  elapsed: number;
+  simulationsPerSecond: number;
+};
+
 type OptimizeAttackMessage =
   | {
-      type: "progress";
-      progress: string;
+      // sent only by webserver, job can be followed after reconnect
+      type: "job";
+      id: string;
     }
+  | ({
+      type: "progress";
+    } & OptimizeAttackProgress)
   | {
       type: "result";
       result: Frame[];
+    }
+  | {
+      type: "error";
+      message: string;
     };
 
 export type {
   Map,
+  DropZoneRulesPreset,
   BuildingType,
   GenericBuilding,
   ClanCastleBuilding,
   Building,
+  TrapZone,
   Shape,
   Frame,
   Unit,
@@ -153,6 +220,11 @@ export type {
   Spell,
   SpellWithCount,
   SpellType,
+  AttackObjective,
+  AvailableArmy,
+  OptimizeAttackStage,
+  OptimizeAttackClientMessage,
+  OptimizeAttackProgress,
   OptimizeAttackMessage,
   WithCount,
   WithName,